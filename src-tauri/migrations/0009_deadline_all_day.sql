@@ -0,0 +1 @@
+ALTER TABLE tasks ADD COLUMN deadline_all_day INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,3 @@
+ALTER TABLE tasks ADD COLUMN archived INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_tasks_archived ON tasks(archived, target_date);
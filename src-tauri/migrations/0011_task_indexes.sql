@@ -0,0 +1,3 @@
+CREATE INDEX IF NOT EXISTS idx_tasks_target_date ON tasks(target_date);
+CREATE INDEX IF NOT EXISTS idx_tasks_status ON tasks(status);
+CREATE INDEX IF NOT EXISTS idx_tasks_target_status ON tasks(target_date, status);
@@ -0,0 +1,4 @@
+ALTER TABLE tasks ADD COLUMN reminder_at TEXT NULL;
+ALTER TABLE tasks ADD COLUMN reminder_state TEXT NULL CHECK(reminder_state IN ('scheduled', 'sent'));
+
+CREATE INDEX IF NOT EXISTS idx_tasks_reminder_due ON tasks(reminder_state, reminder_at);
@@ -0,0 +1,2 @@
+ALTER TABLE checkin_people ADD COLUMN cadence_interval INTEGER NULL;
+ALTER TABLE checkin_people ADD COLUMN cadence_unit TEXT NULL CHECK(cadence_unit IN ('day', 'week', 'month'));
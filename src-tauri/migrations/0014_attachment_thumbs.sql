@@ -0,0 +1 @@
+ALTER TABLE note_attachments ADD COLUMN thumb_path_relative TEXT NULL;
@@ -0,0 +1,3 @@
+ALTER TABLE notes ADD COLUMN task_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_notes_task_id ON notes(task_id);
@@ -0,0 +1 @@
+ALTER TABLE tasks ADD COLUMN rollover_count INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+ALTER TABLE notes ADD COLUMN color TEXT;
+ALTER TABLE notes ADD COLUMN icon TEXT;
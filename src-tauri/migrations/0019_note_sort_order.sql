@@ -0,0 +1,8 @@
+ALTER TABLE notes ADD COLUMN sort_order INTEGER NOT NULL DEFAULT 0;
+
+UPDATE notes
+SET sort_order = rowid
+WHERE sort_order = 0;
+
+CREATE INDEX IF NOT EXISTS idx_notes_folder_sort
+ON notes(folder_id, sort_order);
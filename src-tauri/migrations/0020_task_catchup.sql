@@ -0,0 +1,2 @@
+ALTER TABLE tasks ADD COLUMN catchup_policy TEXT NOT NULL DEFAULT 'skip'
+  CHECK (catchup_policy IN ('skip', 'all'));
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS note_links (
+  source_id TEXT NOT NULL,
+  target_title TEXT NOT NULL,
+  target_id TEXT NULL,
+  PRIMARY KEY (source_id, target_title),
+  FOREIGN KEY(source_id) REFERENCES notes(id) ON DELETE CASCADE,
+  FOREIGN KEY(target_id) REFERENCES notes(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_note_links_target ON note_links(target_id);
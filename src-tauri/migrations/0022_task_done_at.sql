@@ -0,0 +1,7 @@
+ALTER TABLE tasks ADD COLUMN done_at TEXT;
+
+UPDATE tasks
+SET done_at = updated_at
+WHERE status = 'done' AND done_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_tasks_done_at ON tasks(done_at);
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS checkin_attachments (
+  id TEXT PRIMARY KEY,
+  checkin_id TEXT NOT NULL,
+  filename TEXT NOT NULL,
+  path_relative TEXT NOT NULL,
+  thumb_path_relative TEXT,
+  created_at TEXT NOT NULL,
+  FOREIGN KEY(checkin_id) REFERENCES checkins(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_checkin_attachments_checkin_id
+ON checkin_attachments(checkin_id, created_at DESC);
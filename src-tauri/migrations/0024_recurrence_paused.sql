@@ -0,0 +1 @@
+ALTER TABLE tasks ADD COLUMN recurrence_paused INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS timer_sessions (
+  id TEXT PRIMARY KEY,
+  task_id TEXT NOT NULL,
+  started_at TEXT NOT NULL,
+  planned_seconds INTEGER NOT NULL,
+  ended_at TEXT,
+  completed INTEGER NOT NULL DEFAULT 0,
+  FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_timer_sessions_task_id
+ON timer_sessions(task_id, started_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_timer_sessions_ended_at
+ON timer_sessions(ended_at);
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS task_progress_history (
+  task_id TEXT NOT NULL,
+  percent INTEGER NOT NULL,
+  recorded_at TEXT NOT NULL,
+  FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_progress_history_task
+ON task_progress_history(task_id, recorded_at);
@@ -0,0 +1 @@
+ALTER TABLE tasks ADD COLUMN recurrence_reset_notes INTEGER NOT NULL DEFAULT 0;
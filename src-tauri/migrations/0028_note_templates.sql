@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS note_templates (
+  id TEXT PRIMARY KEY,
+  name TEXT NOT NULL,
+  body_markdown TEXT NOT NULL DEFAULT '',
+  tags TEXT NOT NULL DEFAULT '',
+  created_at TEXT NOT NULL,
+  updated_at TEXT NOT NULL
+);
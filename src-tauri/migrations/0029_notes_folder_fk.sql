@@ -0,0 +1,35 @@
+PRAGMA foreign_keys=off;
+
+CREATE TABLE notes_new (
+  id TEXT PRIMARY KEY,
+  title TEXT NOT NULL,
+  body_markdown TEXT NOT NULL DEFAULT '',
+  tags TEXT NOT NULL DEFAULT '',
+  folder_id TEXT,
+  task_id TEXT,
+  color TEXT,
+  icon TEXT,
+  sort_order INTEGER NOT NULL DEFAULT 0,
+  created_at TEXT NOT NULL,
+  updated_at TEXT NOT NULL,
+  FOREIGN KEY(folder_id) REFERENCES note_folders(id) ON DELETE SET NULL
+);
+
+INSERT INTO notes_new (id, title, body_markdown, tags, folder_id, task_id, color, icon, sort_order,
+  created_at, updated_at)
+SELECT n.id, n.title, n.body_markdown, n.tags,
+  CASE WHEN EXISTS (SELECT 1 FROM note_folders f WHERE f.id = n.folder_id) THEN n.folder_id END,
+  n.task_id, n.color, n.icon, n.sort_order, n.created_at, n.updated_at
+FROM notes n;
+
+-- Build the new table under a temporary name and rename it last: renaming `notes` itself
+-- would rewrite the note_links/note_attachments foreign keys to the old table.
+DROP TABLE notes;
+ALTER TABLE notes_new RENAME TO notes;
+
+CREATE INDEX IF NOT EXISTS idx_notes_updated_at ON notes(updated_at DESC);
+CREATE INDEX IF NOT EXISTS idx_notes_folder_id ON notes(folder_id);
+CREATE INDEX IF NOT EXISTS idx_notes_task_id ON notes(task_id);
+CREATE INDEX IF NOT EXISTS idx_notes_folder_sort ON notes(folder_id, sort_order);
+
+PRAGMA foreign_keys=on;
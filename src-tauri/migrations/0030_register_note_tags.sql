@@ -0,0 +1,14 @@
+-- Notes keep their tags as CSV; register the existing ones in the shared tag vocabulary.
+WITH RECURSIVE split(rest, tag) AS (
+  SELECT tags || ',', '' FROM notes WHERE tags != ''
+  UNION ALL
+  SELECT substr(rest, instr(rest, ',') + 1), trim(substr(rest, 1, instr(rest, ',') - 1))
+  FROM split
+  WHERE rest != ''
+)
+INSERT INTO tags (id, name, created_at)
+SELECT lower(hex(randomblob(16))), MIN(tag), strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+FROM split
+WHERE tag != ''
+  AND NOT EXISTS (SELECT 1 FROM tags g WHERE lower(g.name) = lower(split.tag))
+GROUP BY lower(tag);
@@ -0,0 +1 @@
+ALTER TABLE tasks ADD COLUMN recurrence_keep_missed INTEGER NOT NULL DEFAULT 0;
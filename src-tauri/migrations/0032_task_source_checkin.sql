@@ -0,0 +1 @@
+ALTER TABLE tasks ADD COLUMN source_checkin_id TEXT REFERENCES checkins(id) ON DELETE SET NULL;
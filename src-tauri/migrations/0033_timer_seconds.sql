@@ -0,0 +1,3 @@
+ALTER TABLE tasks ADD COLUMN timer_seconds INTEGER;
+
+UPDATE tasks SET timer_seconds = timer_minutes * 60 WHERE timer_minutes IS NOT NULL;
@@ -0,0 +1,36 @@
+PRAGMA foreign_keys=off;
+
+CREATE TABLE checkins_new (
+  id TEXT PRIMARY KEY,
+  person_id TEXT NOT NULL,
+  checkin_date TEXT NOT NULL,
+  discussion TEXT NULL,
+  notes TEXT NULL,
+  action_items TEXT NULL,
+  next_checkin_date TEXT NULL,
+  reminder_enabled INTEGER NOT NULL DEFAULT 0,
+  reminder_time TEXT NULL,
+  reminder_state TEXT NULL CHECK(reminder_state IN ('idle', 'scheduled', 'sent', 'acknowledged')),
+  created_at TEXT NOT NULL,
+  updated_at TEXT NOT NULL,
+  FOREIGN KEY(person_id) REFERENCES checkin_people(id) ON DELETE CASCADE
+);
+
+INSERT INTO checkins_new (id, person_id, checkin_date, discussion, notes, action_items,
+  next_checkin_date, reminder_enabled, reminder_time, reminder_state, created_at, updated_at)
+SELECT id, person_id, checkin_date, discussion, notes, action_items, next_checkin_date,
+  reminder_enabled, reminder_time, reminder_state, created_at, updated_at
+FROM checkins;
+
+-- Same rename-last approach as 0029, so checkin_attachments and tasks keep pointing at
+-- `checkins`.
+DROP TABLE checkins;
+ALTER TABLE checkins_new RENAME TO checkins;
+
+CREATE INDEX IF NOT EXISTS idx_checkins_person_date
+ON checkins(person_id, checkin_date DESC);
+
+CREATE INDEX IF NOT EXISTS idx_checkins_reminder_due
+ON checkins(reminder_enabled, next_checkin_date, reminder_time);
+
+PRAGMA foreign_keys=on;
@@ -0,0 +1 @@
+ALTER TABLE tasks ADD COLUMN snooze_until TEXT;
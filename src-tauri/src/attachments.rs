@@ -1,31 +1,464 @@
 use chrono::Utc;
-use std::{fs, path::Path};
+use serde::Serialize;
+use std::{collections::HashSet, fs, path::Path};
 
 #[derive(Debug, Clone)]
 pub struct StoredAttachment {
     pub filename: String,
     pub path_relative: String,
+    pub thumb_path_relative: Option<String>,
+}
+
+const THUMB_MAX_SIDE: u32 = 256;
+
+const MAX_STEM_LEN: usize = 64;
+
+/// Note files predate categories and stay directly under `attachments/<note_id>/`.
+pub const NOTE_CATEGORY: &str = "";
+/// Check-in files live under `attachments/checkins/<checkin_id>/`.
+pub const CHECKIN_CATEGORY: &str = "checkins";
+/// Files found by an attachment audit with no matching row are moved here.
+pub const ORPHANED_DIR: &str = "_orphaned";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NoteAttachmentUsage {
+    pub note_id: String,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceUsage {
+    pub database_bytes: u64,
+    pub attachments_bytes: u64,
+    pub notes: Vec<NoteAttachmentUsage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AttachmentContent {
+    pub filename: String,
+    pub mime: String,
+    pub bytes: Vec<u8>,
 }
 
 pub fn save_note_image(
     workspace: &Path,
     note_id: &str,
     bytes: &[u8],
+) -> Result<StoredAttachment, String> {
+    save_attachment(workspace, NOTE_CATEGORY, note_id, bytes, "image.png")
+}
+
+/// Stores `bytes` under `attachments/<category>/<owner_id>/`, keeping the extension of
+/// `original_name`. Filenames are prefixed with a timestamp so repeated uploads of the
+/// same name never collide.
+pub fn save_attachment(
+    workspace: &Path,
+    category: &str,
+    owner_id: &str,
+    bytes: &[u8],
+    original_name: &str,
 ) -> Result<StoredAttachment, String> {
     if bytes.is_empty() {
         return Err("Attachment is empty".to_string());
     }
 
-    let note_dir = workspace.join("attachments").join(note_id);
+    let dir_relative = owner_dir_relative(category, owner_id)?;
+    let dir = workspace.join(&dir_relative);
+    fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
+
+    let filename = format!(
+        "{}-{}",
+        Utc::now().format("%Y%m%d%H%M%S%3f"),
+        sanitize_filename(original_name)
+    );
+    write_attachment(&dir, &dir_relative, filename, bytes)
+}
+
+/// Removes every file stored for one owner, e.g. when a check-in is deleted.
+pub fn remove_owner_files(workspace: &Path, category: &str, owner_id: &str) -> Result<(), String> {
+    let dir = workspace.join(owner_dir_relative(category, owner_id)?);
+    if dir.exists() {
+        fs::remove_dir_all(&dir).map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+fn owner_dir_relative(category: &str, owner_id: &str) -> Result<String, String> {
+    if owner_id.is_empty() {
+        return Err("Attachment owner is required".to_string());
+    }
+    for segment in [category, owner_id] {
+        if segment.contains(['/', '\\']) || segment == "." || segment == ".." {
+            return Err(format!("Invalid attachment path segment: {}", segment));
+        }
+    }
+    Ok(["attachments", category, owner_id]
+        .iter()
+        .filter(|part| !part.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join("/"))
+}
+
+/// Reduces a user-supplied name to `stem.ext` made of ASCII letters, digits, `-` and
+/// `_`. Directory parts are dropped and an unusable extension becomes `bin`.
+fn sanitize_filename(original_name: &str) -> String {
+    let base = original_name
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .trim();
+    let (stem, ext) = match base.rfind('.') {
+        Some(index) => (&base[..index], &base[index + 1..]),
+        None => (base, ""),
+    };
+
+    let mut clean = String::new();
+    for ch in stem.chars() {
+        if ch.is_ascii_alphanumeric() || ch == '_' || ch == '-' {
+            clean.push(ch);
+        } else if !clean.is_empty() && !clean.ends_with('-') {
+            clean.push('-');
+        }
+        if clean.len() >= MAX_STEM_LEN {
+            break;
+        }
+    }
+    let clean = clean.trim_end_matches('-');
+    let clean = if clean.is_empty() { "file" } else { clean };
+    format!("{}.{}", clean, sanitize_extension(ext))
+}
+
+fn sanitize_extension(ext: &str) -> String {
+    let ext = ext.trim().to_lowercase();
+    if ext.is_empty() || ext.len() > 8 || !ext.chars().all(|ch| ch.is_ascii_alphanumeric()) {
+        "bin".to_string()
+    } else {
+        ext
+    }
+}
+
+/// Saves several images for one note. Filenames share a timestamp and carry the input
+/// index so results map back to inputs; if any write fails, the ones already written
+/// are removed.
+pub fn save_note_images(
+    workspace: &Path,
+    note_id: &str,
+    files: &[Vec<u8>],
+) -> Result<Vec<StoredAttachment>, String> {
+    if files.is_empty() {
+        return Err("No attachments to save".to_string());
+    }
+    if files.iter().any(|bytes| bytes.is_empty()) {
+        return Err("Attachment is empty".to_string());
+    }
+
+    let dir_relative = owner_dir_relative(NOTE_CATEGORY, note_id)?;
+    let note_dir = workspace.join(&dir_relative);
     fs::create_dir_all(&note_dir).map_err(|err| err.to_string())?;
 
-    let filename = format!("{}.png", Utc::now().format("%Y%m%d%H%M%S%3f"));
-    let full_path = note_dir.join(&filename);
-    fs::write(&full_path, bytes).map_err(|err| err.to_string())?;
+    let stamp = Utc::now().format("%Y%m%d%H%M%S%3f").to_string();
+    let mut saved = Vec::with_capacity(files.len());
+    for (index, bytes) in files.iter().enumerate() {
+        let filename = format!("{}-{}.png", stamp, index + 1);
+        match write_attachment(&note_dir, &dir_relative, filename, bytes) {
+            Ok(stored) => saved.push(stored),
+            Err(err) => {
+                remove_stored(workspace, &saved);
+                return Err(err);
+            }
+        }
+    }
+    Ok(saved)
+}
+
+/// Best-effort removal of stored files and their thumbnails.
+pub fn remove_stored(workspace: &Path, stored: &[StoredAttachment]) {
+    for item in stored {
+        let _ = fs::remove_file(workspace.join(&item.path_relative));
+        if let Some(thumb) = &item.thumb_path_relative {
+            let _ = fs::remove_file(workspace.join(thumb));
+        }
+    }
+}
+
+fn write_attachment(
+    dir: &Path,
+    dir_relative: &str,
+    filename: String,
+    bytes: &[u8],
+) -> Result<StoredAttachment, String> {
+    fs::write(dir.join(&filename), bytes).map_err(|err| err.to_string())?;
 
-    let path_relative = format!("attachments/{}/{}", note_id, filename);
+    let path_relative = format!("{}/{}", dir_relative, filename);
+    let thumb_name = Path::new(&filename)
+        .with_extension("png")
+        .to_string_lossy()
+        .to_string();
+    let thumb_path_relative = save_thumbnail(dir, bytes, &thumb_name)
+        .map(|_| format!("{}/thumbs/{}", dir_relative, thumb_name));
     Ok(StoredAttachment {
         filename,
         path_relative,
+        thumb_path_relative,
     })
 }
+
+/// Writes a downscaled PNG next to the original. Returns `None` when the bytes are not
+/// a decodable image, since previews are optional.
+fn save_thumbnail(dir: &Path, bytes: &[u8], filename: &str) -> Option<()> {
+    let image = image::load_from_memory(bytes).ok()?;
+    let thumb = image.thumbnail(THUMB_MAX_SIDE, THUMB_MAX_SIDE);
+    let thumbs_dir = dir.join("thumbs");
+    fs::create_dir_all(&thumbs_dir).ok()?;
+    thumb
+        .save_with_format(thumbs_dir.join(filename), image::ImageFormat::Png)
+        .ok()
+}
+
+fn mime_for(filename: &str) -> &'static str {
+    let extension = Path::new(filename)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "pdf" => "application/pdf",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Reads a stored attachment, refusing any path that resolves outside `attachments/`.
+pub fn read_attachment(
+    workspace: &Path,
+    filename: &str,
+    path_relative: &str,
+) -> Result<AttachmentContent, String> {
+    let root = workspace
+        .join("attachments")
+        .canonicalize()
+        .map_err(|_| "Attachment file is missing".to_string())?;
+    let full_path = workspace
+        .join(path_relative)
+        .canonicalize()
+        .map_err(|_| format!("Attachment file is missing: {}", path_relative))?;
+    if !full_path.starts_with(&root) {
+        return Err("Attachment path is outside the workspace".to_string());
+    }
+
+    let bytes = fs::read(&full_path).map_err(|err| err.to_string())?;
+    Ok(AttachmentContent {
+        filename: filename.to_string(),
+        mime: mime_for(filename).to_string(),
+        bytes,
+    })
+}
+
+/// Lists note files under `attachments/` whose relative path is not in `known`.
+/// Check-in files and previously quarantined files are left out.
+pub fn find_orphaned_note_files(
+    workspace: &Path,
+    known: &HashSet<String>,
+) -> Result<Vec<String>, String> {
+    let attachments_dir = workspace.join("attachments");
+    let mut orphaned = Vec::new();
+    if !attachments_dir.exists() {
+        return Ok(orphaned);
+    }
+    for entry in fs::read_dir(&attachments_dir).map_err(|err| err.to_string())? {
+        let entry = entry.map_err(|err| err.to_string())?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name == CHECKIN_CATEGORY || name == ORPHANED_DIR {
+            continue;
+        }
+        collect_files(
+            &entry.path(),
+            &format!("attachments/{}", name),
+            &mut orphaned,
+        )?;
+    }
+    orphaned.retain(|path| !known.contains(path));
+    orphaned.sort();
+    Ok(orphaned)
+}
+
+fn collect_files(path: &Path, relative: &str, files: &mut Vec<String>) -> Result<(), String> {
+    if !path.is_dir() {
+        files.push(relative.to_string());
+        return Ok(());
+    }
+    for entry in fs::read_dir(path).map_err(|err| err.to_string())? {
+        let entry = entry.map_err(|err| err.to_string())?;
+        let child = format!("{}/{}", relative, entry.file_name().to_string_lossy());
+        collect_files(&entry.path(), &child, files)?;
+    }
+    Ok(())
+}
+
+/// Moves a file from `attachments/<rest>` to `attachments/_orphaned/<rest>` and returns
+/// the new relative path.
+pub fn move_to_orphaned(workspace: &Path, path_relative: &str) -> Result<String, String> {
+    let rest = path_relative
+        .strip_prefix("attachments/")
+        .ok_or_else(|| format!("Not an attachment path: {}", path_relative))?;
+    let target_relative = format!("attachments/{}/{}", ORPHANED_DIR, rest);
+    let target = workspace.join(&target_relative);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    fs::rename(workspace.join(path_relative), &target).map_err(|err| err.to_string())?;
+    Ok(target_relative)
+}
+
+/// Rejects saving `incoming` files (sizes in bytes) for an owner that already has the
+/// files at `existing` (workspace-relative paths) when either cap would be exceeded.
+/// Existing files missing on disk count as zero bytes.
+pub fn check_owner_limits(
+    workspace: &Path,
+    existing: &[String],
+    incoming: &[u64],
+    max_count: usize,
+    max_bytes: u64,
+) -> Result<(), String> {
+    if existing.len() + incoming.len() > max_count {
+        return Err(format!(
+            "A note can have at most {} attachments; it already has {}",
+            max_count,
+            existing.len()
+        ));
+    }
+    let used: u64 = existing
+        .iter()
+        .map(|path| {
+            fs::metadata(workspace.join(path))
+                .map(|metadata| metadata.len())
+                .unwrap_or(0)
+        })
+        .sum();
+    let total = used + incoming.iter().sum::<u64>();
+    if total > max_bytes {
+        return Err(format!(
+            "Attachments on a note are limited to {}; saving would bring it to {}",
+            format_megabytes(max_bytes),
+            format_megabytes(total)
+        ));
+    }
+    Ok(())
+}
+
+fn format_megabytes(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+fn dir_size(path: &Path) -> Result<u64, String> {
+    let mut total = 0;
+    for entry in fs::read_dir(path).map_err(|err| err.to_string())? {
+        let entry = entry.map_err(|err| err.to_string())?;
+        let metadata = entry.metadata().map_err(|err| err.to_string())?;
+        if metadata.is_dir() {
+            total += dir_size(&entry.path())?;
+        } else {
+            total += metadata.len();
+        }
+    }
+    Ok(total)
+}
+
+pub fn workspace_usage(workspace: &Path) -> Result<WorkspaceUsage, String> {
+    let database_bytes = fs::metadata(workspace.join("dayrally.sqlite"))
+        .map(|metadata| metadata.len())
+        .unwrap_or(0);
+
+    let attachments_dir = workspace.join("attachments");
+    let mut attachments_bytes = 0;
+    let mut notes = Vec::new();
+    if attachments_dir.exists() {
+        for entry in fs::read_dir(&attachments_dir).map_err(|err| err.to_string())? {
+            let entry = entry.map_err(|err| err.to_string())?;
+            let metadata = entry.metadata().map_err(|err| err.to_string())?;
+            if !metadata.is_dir() {
+                attachments_bytes += metadata.len();
+                continue;
+            }
+            let bytes = dir_size(&entry.path())?;
+            attachments_bytes += bytes;
+            if entry.file_name() == CHECKIN_CATEGORY || entry.file_name() == ORPHANED_DIR {
+                continue;
+            }
+            notes.push(NoteAttachmentUsage {
+                note_id: entry.file_name().to_string_lossy().to_string(),
+                bytes,
+            });
+        }
+    }
+    notes.sort_by_key(|usage| std::cmp::Reverse(usage.bytes));
+
+    Ok(WorkspaceUsage {
+        database_bytes,
+        attachments_bytes,
+        notes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn test_workspace() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("dayrally-test-{}", Uuid::new_v4()))
+    }
+
+    #[test]
+    fn sanitize_filename_keeps_extension_and_strips_unsafe_characters() {
+        assert_eq!(
+            sanitize_filename("Meeting notes (v2).PDF"),
+            "Meeting-notes-v2.pdf"
+        );
+        assert_eq!(sanitize_filename("../../etc/passwd"), "passwd.bin");
+        assert_eq!(sanitize_filename("C:\\Users\\me\\photo.jpeg"), "photo.jpeg");
+        assert_eq!(sanitize_filename(".png"), "file.png");
+        assert_eq!(sanitize_filename("archive.tar.gz"), "archive-tar.gz");
+        assert_eq!(sanitize_filename("weird.ex e"), "weird.bin");
+        assert_eq!(sanitize_filename(""), "file.bin");
+    }
+
+    #[test]
+    fn owner_limits_count_existing_files_and_bytes() {
+        let workspace = test_workspace();
+        let stored = save_attachment(&workspace, NOTE_CATEGORY, "n1", &[1; 600], "a.bin").unwrap();
+        let existing = vec![stored.path_relative];
+        assert!(check_owner_limits(&workspace, &existing, &[400], 2, 1000).is_ok());
+        assert!(check_owner_limits(&workspace, &existing, &[401], 2, 1000)
+            .unwrap_err()
+            .contains("limited to"));
+        assert!(check_owner_limits(&workspace, &existing, &[1, 1], 2, 1000)
+            .unwrap_err()
+            .contains("at most 2"));
+        fs::remove_dir_all(&workspace).unwrap();
+    }
+
+    #[test]
+    fn save_attachment_rejects_empty_bytes() {
+        let workspace = test_workspace();
+        let result = save_attachment(&workspace, CHECKIN_CATEGORY, "c1", &[], "a.png");
+        assert_eq!(result.unwrap_err(), "Attachment is empty");
+        assert!(!workspace.exists());
+    }
+
+    #[test]
+    fn save_attachment_stores_under_category_and_owner() {
+        let workspace = test_workspace();
+        let stored =
+            save_attachment(&workspace, CHECKIN_CATEGORY, "c1", b"hello", "Agenda.txt").unwrap();
+        assert!(stored.path_relative.starts_with("attachments/checkins/c1/"));
+        assert!(stored.filename.ends_with("-Agenda.txt"));
+        assert!(stored.thumb_path_relative.is_none());
+        assert!(workspace.join(&stored.path_relative).exists());
+        assert!(save_attachment(&workspace, "../x", "c1", b"hello", "a.txt").is_err());
+        let _ = fs::remove_dir_all(&workspace);
+    }
+}
@@ -1,5 +1,6 @@
 use chrono::Utc;
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OpenFlags};
+use serde::Serialize;
 use std::{fs, path::Path};
 
 const MIGRATIONS: &[(i32, &str)] = &[
@@ -11,8 +12,239 @@ const MIGRATIONS: &[(i32, &str)] = &[
     (6, include_str!("../migrations/0006_note_folders.sql")),
     (7, include_str!("../migrations/0007_checkins.sql")),
     (8, include_str!("../migrations/0008_task_tags_column.sql")),
+    (9, include_str!("../migrations/0009_deadline_all_day.sql")),
+    (10, include_str!("../migrations/0010_task_archived.sql")),
+    (11, include_str!("../migrations/0011_task_indexes.sql")),
+    (12, include_str!("../migrations/0012_task_reminders.sql")),
+    (13, include_str!("../migrations/0013_checkin_cadence.sql")),
+    (14, include_str!("../migrations/0014_attachment_thumbs.sql")),
+    (15, include_str!("../migrations/0015_task_dependencies.sql")),
+    (16, include_str!("../migrations/0016_note_task_link.sql")),
+    (17, include_str!("../migrations/0017_rollover_count.sql")),
+    (18, include_str!("../migrations/0018_note_style.sql")),
+    (19, include_str!("../migrations/0019_note_sort_order.sql")),
+    (20, include_str!("../migrations/0020_task_catchup.sql")),
+    (21, include_str!("../migrations/0021_note_links.sql")),
+    (22, include_str!("../migrations/0022_task_done_at.sql")),
+    (
+        23,
+        include_str!("../migrations/0023_checkin_attachments.sql"),
+    ),
+    (24, include_str!("../migrations/0024_recurrence_paused.sql")),
+    (25, include_str!("../migrations/0025_timer_sessions.sql")),
+    (
+        26,
+        include_str!("../migrations/0026_task_progress_history.sql"),
+    ),
+    (
+        27,
+        include_str!("../migrations/0027_recurrence_reset_notes.sql"),
+    ),
+    (28, include_str!("../migrations/0028_note_templates.sql")),
+    (29, include_str!("../migrations/0029_notes_folder_fk.sql")),
+    (
+        30,
+        include_str!("../migrations/0030_register_note_tags.sql"),
+    ),
+    (
+        31,
+        include_str!("../migrations/0031_recurrence_keep_missed.sql"),
+    ),
+    (
+        32,
+        include_str!("../migrations/0032_task_source_checkin.sql"),
+    ),
+    (33, include_str!("../migrations/0033_timer_seconds.sql")),
+    (
+        34,
+        include_str!("../migrations/0034_checkin_reminder_acknowledged.sql"),
+    ),
+    (35, include_str!("../migrations/0035_projects.sql")),
+    (36, include_str!("../migrations/0036_task_snooze.sql")),
 ];
 
+/// Tables and columns the current migrations are expected to produce.
+const EXPECTED_TABLES: &[(&str, &[&str])] = &[
+    ("schema_migrations", &["version", "applied_at"]),
+    (
+        "tasks",
+        &[
+            "id",
+            "title",
+            "notes",
+            "target_date",
+            "status",
+            "progress_percent",
+            "deadline_at",
+            "deadline_all_day",
+            "is_recurring",
+            "recurrence_type",
+            "recurrence_interval",
+            "recurrence_weekdays",
+            "timer_enabled",
+            "timer_minutes",
+            "timer_seconds",
+            "timer_state",
+            "timer_ends_at",
+            "rolled_over",
+            "rolled_from_date",
+            "rollover_count",
+            "catchup_policy",
+            "recurrence_paused",
+            "recurrence_reset_notes",
+            "recurrence_keep_missed",
+            "done_at",
+            "sort_order",
+            "tags",
+            "archived",
+            "reminder_at",
+            "reminder_state",
+            "source_checkin_id",
+            "project_id",
+            "snooze_until",
+            "created_at",
+            "updated_at",
+        ],
+    ),
+    ("tags", &["id", "name", "created_at"]),
+    ("task_tags", &["task_id", "tag_id"]),
+    (
+        "task_dependencies",
+        &["task_id", "depends_on_id", "created_at"],
+    ),
+    (
+        "task_progress_history",
+        &["task_id", "percent", "recorded_at"],
+    ),
+    (
+        "timer_sessions",
+        &[
+            "id",
+            "task_id",
+            "started_at",
+            "planned_seconds",
+            "ended_at",
+            "completed",
+        ],
+    ),
+    (
+        "notes",
+        &[
+            "id",
+            "title",
+            "body_markdown",
+            "tags",
+            "folder_id",
+            "task_id",
+            "color",
+            "icon",
+            "sort_order",
+            "created_at",
+            "updated_at",
+        ],
+    ),
+    ("note_links", &["source_id", "target_title", "target_id"]),
+    (
+        "note_templates",
+        &[
+            "id",
+            "name",
+            "body_markdown",
+            "tags",
+            "created_at",
+            "updated_at",
+        ],
+    ),
+    (
+        "note_attachments",
+        &[
+            "id",
+            "note_id",
+            "filename",
+            "path_relative",
+            "thumb_path_relative",
+            "created_at",
+        ],
+    ),
+    ("note_folders", &["id", "name", "created_at", "updated_at"]),
+    (
+        "projects",
+        &["id", "name", "color", "created_at", "updated_at"],
+    ),
+    (
+        "checkin_people",
+        &[
+            "id",
+            "name",
+            "relationship",
+            "cadence_interval",
+            "cadence_unit",
+            "created_at",
+            "updated_at",
+        ],
+    ),
+    (
+        "checkins",
+        &[
+            "id",
+            "person_id",
+            "checkin_date",
+            "discussion",
+            "notes",
+            "action_items",
+            "next_checkin_date",
+            "reminder_enabled",
+            "reminder_time",
+            "reminder_state",
+            "created_at",
+            "updated_at",
+        ],
+    ),
+    (
+        "checkin_attachments",
+        &[
+            "id",
+            "checkin_id",
+            "filename",
+            "path_relative",
+            "thumb_path_relative",
+            "created_at",
+        ],
+    ),
+];
+
+const EXPECTED_INDEXES: &[&str] = &[
+    "idx_tasks_target_date",
+    "idx_tasks_status",
+    "idx_tasks_target_roll_sort",
+    "idx_tasks_archived",
+    "idx_tasks_target_status",
+    "idx_tasks_reminder_due",
+    "idx_task_tags_task",
+    "idx_task_tags_tag",
+    "idx_task_dependencies_depends_on",
+    "idx_timer_sessions_task_id",
+    "idx_task_progress_history_task",
+    "idx_timer_sessions_ended_at",
+    "idx_notes_updated_at",
+    "idx_notes_folder_id",
+    "idx_notes_task_id",
+    "idx_notes_folder_sort",
+    "idx_tasks_done_at",
+    "idx_note_links_target",
+    "idx_note_attachments_note_id",
+    "idx_checkin_attachments_checkin_id",
+    "idx_checkins_person_date",
+    "idx_checkins_reminder_due",
+    "idx_tasks_project_id",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaReport {
+    pub ok: bool,
+    pub issues: Vec<String>,
+}
+
 pub fn ensure_workspace(path: &Path) -> Result<(), String> {
     if !path.exists() {
         fs::create_dir_all(path).map_err(|err| err.to_string())?;
@@ -35,6 +267,169 @@ pub fn open_db(path: &Path) -> Result<Connection, String> {
     Ok(conn)
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaVersion {
+    /// Highest migration recorded in the database; 0 before any has run.
+    pub database_version: i32,
+    /// Highest migration this build knows about.
+    pub app_version: i32,
+    /// "current", "behind" (migrations pending) or "ahead" (written by a newer build).
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VacuumReport {
+    pub before_bytes: u64,
+    pub after_bytes: u64,
+}
+
+/// Database file plus its WAL, if one exists.
+fn database_size(path: &Path) -> u64 {
+    ["dayrally.sqlite", "dayrally.sqlite-wal"]
+        .iter()
+        .filter_map(|name| fs::metadata(path.join(name)).ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// Rebuilds the workspace database so space freed by deletes is returned to the disk.
+/// Uses its own connection; VACUUM refuses to run inside a transaction.
+pub fn vacuum(path: &Path) -> Result<VacuumReport, String> {
+    let before_bytes = database_size(path);
+    let conn = open_db(path)?;
+    if !conn.is_autocommit() {
+        return Err("Cannot vacuum while a transaction is open".to_string());
+    }
+    let journal_mode: String = conn
+        .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+        .map_err(|err| err.to_string())?;
+    conn.execute_batch("VACUUM;")
+        .map_err(|err| err.to_string())?;
+    if journal_mode.eq_ignore_ascii_case("wal") {
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+            .map_err(|err| err.to_string())?;
+    }
+    drop(conn);
+    Ok(VacuumReport {
+        before_bytes,
+        after_bytes: database_size(path),
+    })
+}
+
+/// Opens the workspace database without running migrations, for diagnostics.
+pub fn open_db_readonly(path: &Path) -> Result<Connection, String> {
+    let db_path = path.join("dayrally.sqlite");
+    if !db_path.exists() {
+        return Err("Database not found".to_string());
+    }
+    Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|err| err.to_string())
+}
+
+/// Compares the database's migration level with this build's. Does not migrate.
+pub fn schema_version(conn: &Connection) -> Result<SchemaVersion, String> {
+    let has_table: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'schema_migrations')",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+    let database_version: i32 = if has_table {
+        conn.query_row(
+            "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?
+    } else {
+        0
+    };
+    let app_version = MIGRATIONS
+        .iter()
+        .map(|(version, _)| *version)
+        .max()
+        .unwrap_or(0);
+    let status = match database_version.cmp(&app_version) {
+        std::cmp::Ordering::Less => "behind",
+        std::cmp::Ordering::Equal => "current",
+        std::cmp::Ordering::Greater => "ahead",
+    };
+    Ok(SchemaVersion {
+        database_version,
+        app_version,
+        status: status.to_string(),
+    })
+}
+
+fn table_columns(conn: &Connection, table: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({})", table))
+        .map_err(|err| err.to_string())?;
+    let columns = stmt
+        .query_map([], |row| row.get(1))
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    Ok(columns)
+}
+
+pub fn verify_schema(conn: &Connection) -> Result<SchemaReport, String> {
+    let mut issues = Vec::new();
+
+    for (table, expected_columns) in EXPECTED_TABLES {
+        let columns = table_columns(conn, table)?;
+        if columns.is_empty() {
+            issues.push(format!("Missing table: {}", table));
+            continue;
+        }
+        for column in *expected_columns {
+            if !columns.iter().any(|name| name == column) {
+                issues.push(format!("Missing column: {}.{}", table, column));
+            }
+        }
+    }
+
+    let mut stmt = conn
+        .prepare("SELECT name FROM sqlite_master WHERE type = 'index'")
+        .map_err(|err| err.to_string())?;
+    let indexes: Vec<String> = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    for index in EXPECTED_INDEXES {
+        if !indexes.iter().any(|name| name == index) {
+            issues.push(format!("Missing index: {}", index));
+        }
+    }
+
+    if !table_columns(conn, "schema_migrations")?.is_empty() {
+        let mut stmt = conn
+            .prepare("SELECT version FROM schema_migrations")
+            .map_err(|err| err.to_string())?;
+        let applied: Vec<i32> = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(|err| err.to_string())?
+            .filter_map(Result::ok)
+            .collect();
+        for (version, _) in MIGRATIONS {
+            if !applied.contains(version) {
+                issues.push(format!("Migration {} not recorded", version));
+            }
+        }
+    }
+
+    if task_tags_references_old_tasks(conn)? {
+        issues.push("task_tags still references tasks_old".to_string());
+    }
+
+    Ok(SchemaReport {
+        ok: issues.is_empty(),
+        issues,
+    })
+}
+
 fn run_migrations(conn: &Connection) -> Result<(), String> {
     conn
     .execute(
@@ -163,3 +558,206 @@ fn rebuild_task_tags_table(conn: &Connection) -> Result<(), String> {
     let _ = conn.execute_batch("PRAGMA foreign_keys = ON;");
     rebuild.map_err(|err| err.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migrated() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        ensure_task_tags_schema(&conn).unwrap();
+        conn
+    }
+
+    fn query_plan(conn: &Connection, sql: &str) -> String {
+        let mut stmt = conn
+            .prepare(&format!("EXPLAIN QUERY PLAN {}", sql))
+            .unwrap();
+        let details: Vec<String> = stmt
+            .query_map(["2026-02-06"], |row| row.get(3))
+            .unwrap()
+            .filter_map(Result::ok)
+            .collect();
+        details.join("\n")
+    }
+
+    #[test]
+    fn migrated_schema_passes_verification() {
+        let report = verify_schema(&migrated()).unwrap();
+        assert!(report.ok, "{:?}", report.issues);
+    }
+
+    #[test]
+    fn verification_reports_missing_pieces() {
+        let conn = migrated();
+        conn.execute_batch(
+            "DROP INDEX idx_tasks_status; DELETE FROM schema_migrations WHERE version = 3;",
+        )
+        .unwrap();
+        let report = verify_schema(&conn).unwrap();
+        assert!(!report.ok);
+        assert!(report
+            .issues
+            .contains(&"Missing index: idx_tasks_status".to_string()));
+        assert!(report
+            .issues
+            .contains(&"Migration 3 not recorded".to_string()));
+    }
+
+    #[test]
+    fn deleting_a_folder_unfiles_its_notes() {
+        let conn = migrated();
+        conn.execute_batch(
+            "INSERT INTO note_folders (id, name, created_at, updated_at) VALUES ('f1', 'Work', 'x', 'x');
+             INSERT INTO notes (id, title, folder_id, created_at, updated_at) VALUES ('n1', 'A', 'f1', 'x', 'x');
+             DELETE FROM note_folders WHERE id = 'f1';",
+        )
+        .unwrap();
+        let folder_id: Option<String> = conn
+            .query_row("SELECT folder_id FROM notes WHERE id = 'n1'", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(folder_id, None);
+        assert!(conn
+            .execute("UPDATE notes SET folder_id = 'missing' WHERE id = 'n1'", [])
+            .is_err());
+
+        let mut stmt = conn.prepare("PRAGMA foreign_key_list(note_links)").unwrap();
+        let referenced: Vec<String> = stmt
+            .query_map([], |row| row.get(2))
+            .unwrap()
+            .filter_map(Result::ok)
+            .collect();
+        assert!(referenced.iter().all(|table| table == "notes"));
+    }
+
+    #[test]
+    fn note_tags_are_registered_once_per_name() {
+        let conn = migrated();
+        conn.execute_batch(
+            "INSERT INTO tags (id, name, created_at) VALUES ('t1', 'Work', 'x');
+             INSERT INTO notes (id, title, tags, created_at, updated_at)
+             VALUES ('n1', 'A', 'work, Retro,retro', 'x', 'x'), ('n2', 'B', ' ideas ', 'x', 'x');
+             DELETE FROM schema_migrations WHERE version = 30;",
+        )
+        .unwrap();
+        run_migrations(&conn).unwrap();
+        let mut stmt = conn
+            .prepare("SELECT lower(name) FROM tags ORDER BY lower(name)")
+            .unwrap();
+        let names: Vec<String> = stmt
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .filter_map(Result::ok)
+            .collect();
+        assert_eq!(names, vec!["ideas", "retro", "work"]);
+    }
+
+    #[test]
+    fn acknowledged_reminder_migration_keeps_checkin_references() {
+        let conn = migrated();
+        conn.execute_batch(
+            "INSERT INTO checkin_people (id, name, relationship, created_at, updated_at)
+             VALUES ('p1', 'Sam', 'peer', 'x', 'x');
+             INSERT INTO checkins (id, person_id, checkin_date, next_checkin_date, reminder_enabled,
+               reminder_time, reminder_state, created_at, updated_at)
+             VALUES ('c1', 'p1', '2026-01-01', '2026-01-08', 1, '09:00', 'scheduled', 'x', 'x'),
+                    ('c2', 'p1', '2026-01-02', '2026-01-08', 1, '09:00', 'scheduled', 'x', 'x');
+             INSERT INTO tasks (id, title, target_date, source_checkin_id, created_at, updated_at)
+             VALUES ('t1', 'Follow up', '2026-01-03', 'c1', 'x', 'x');
+             DELETE FROM schema_migrations WHERE version = 34;",
+        )
+        .unwrap();
+        run_migrations(&conn).unwrap();
+
+        for table in ["checkin_attachments", "tasks"] {
+            let mut stmt = conn
+                .prepare(&format!("PRAGMA foreign_key_list({})", table))
+                .unwrap();
+            let referenced: Vec<String> = stmt
+                .query_map([], |row| row.get(2))
+                .unwrap()
+                .filter_map(Result::ok)
+                .collect();
+            assert!(referenced.contains(&"checkins".to_string()), "{}", table);
+        }
+
+        conn.execute(
+            "UPDATE checkins SET reminder_state = 'acknowledged' WHERE id = 'c2'",
+            [],
+        )
+        .unwrap();
+        let due = crate::repository::list_due_checkin_reminders(&conn, chrono::Local::now())
+            .unwrap()
+            .into_iter()
+            .map(|reminder| reminder.checkin_id)
+            .collect::<Vec<_>>();
+        assert_eq!(due, vec!["c1"]);
+
+        conn.execute("DELETE FROM checkins WHERE id = 'c1'", [])
+            .unwrap();
+        let source: Option<String> = conn
+            .query_row(
+                "SELECT source_checkin_id FROM tasks WHERE id = 't1'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(source, None);
+    }
+
+    #[test]
+    fn schema_version_compares_database_and_app() {
+        let conn = migrated();
+        let report = schema_version(&conn).unwrap();
+        assert_eq!(report.status, "current");
+        assert_eq!(report.database_version, report.app_version);
+
+        conn.execute(
+            "DELETE FROM schema_migrations WHERE version = ?1",
+            params![report.app_version],
+        )
+        .unwrap();
+        assert_eq!(schema_version(&conn).unwrap().status, "behind");
+        assert_eq!(
+            schema_version(&Connection::open_in_memory().unwrap())
+                .unwrap()
+                .database_version,
+            0
+        );
+    }
+
+    #[test]
+    fn vacuum_shrinks_the_file_after_deletes() {
+        let workspace =
+            std::env::temp_dir().join(format!("dayrally-vacuum-{}", uuid::Uuid::new_v4()));
+        let conn = open_db(&workspace).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE filler (body TEXT);
+             WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 2000)
+             INSERT INTO filler SELECT hex(randomblob(256)) FROM n;
+             DELETE FROM filler;",
+        )
+        .unwrap();
+        drop(conn);
+        let report = vacuum(&workspace).unwrap();
+        assert!(report.after_bytes < report.before_bytes);
+        fs::remove_dir_all(&workspace).unwrap();
+    }
+
+    #[test]
+    fn overview_and_rollover_queries_use_indexes() {
+        let conn = migrated();
+        for sql in [
+            "SELECT * FROM tasks WHERE target_date = ?1 AND rolled_over = 0 AND archived = 0",
+            "SELECT * FROM tasks WHERE target_date > ?1 AND archived = 0",
+            "SELECT id, target_date FROM tasks WHERE target_date < ?1 AND status != 'done' AND archived = 0",
+        ] {
+            let plan = query_plan(&conn, sql);
+            assert!(plan.contains("USING"), "{} -> {}", sql, plan);
+            assert!(!plan.contains("SCAN tasks"), "{} -> {}", sql, plan);
+        }
+    }
+}
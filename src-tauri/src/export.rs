@@ -0,0 +1,255 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rusqlite::{types::ValueRef, Connection};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::repository::Task;
+use crate::services::deadline::{parse_deadline, Deadline};
+
+/// Entities in the order they are written; parents come before the rows that reference them.
+const ENTITY_ORDER: &[(&str, &str)] = &[
+    ("project", "SELECT * FROM projects ORDER BY created_at ASC"),
+    ("task", "SELECT * FROM tasks ORDER BY created_at ASC"),
+    ("tag", "SELECT * FROM tags ORDER BY created_at ASC"),
+    ("task_tag", "SELECT * FROM task_tags"),
+    ("task_dependency", "SELECT * FROM task_dependencies"),
+    (
+        "task_progress",
+        "SELECT * FROM task_progress_history ORDER BY recorded_at ASC",
+    ),
+    (
+        "timer_session",
+        "SELECT * FROM timer_sessions ORDER BY started_at ASC",
+    ),
+    (
+        "note_folder",
+        "SELECT * FROM note_folders ORDER BY created_at ASC",
+    ),
+    ("note", "SELECT * FROM notes ORDER BY created_at ASC"),
+    (
+        "note_template",
+        "SELECT * FROM note_templates ORDER BY created_at ASC",
+    ),
+    (
+        "note_attachment",
+        "SELECT * FROM note_attachments ORDER BY created_at ASC",
+    ),
+    (
+        "checkin_person",
+        "SELECT * FROM checkin_people ORDER BY created_at ASC",
+    ),
+    ("checkin", "SELECT * FROM checkins ORDER BY created_at ASC"),
+    (
+        "checkin_attachment",
+        "SELECT * FROM checkin_attachments ORDER BY created_at ASC",
+    ),
+];
+
+const FLUSH_EVERY: usize = 500;
+
+/// RFC 5545 limit for a content line, in octets, before it has to be folded.
+const ICS_LINE_LIMIT: usize = 75;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonlExportSummary {
+    pub path: String,
+    pub schema_version: i64,
+    pub records: usize,
+}
+
+pub fn export_workspace_jsonl(
+    conn: &Connection,
+    path: &Path,
+) -> Result<JsonlExportSummary, String> {
+    let schema_version: i64 = conn
+        .query_row(
+            "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+
+    let file = File::create(path).map_err(|err| err.to_string())?;
+    let mut writer = BufWriter::new(file);
+
+    let header = json!({
+        "type": "header",
+        "schema_version": schema_version,
+        "entities": ENTITY_ORDER.iter().map(|(entity, _)| *entity).collect::<Vec<_>>(),
+    });
+    write_line(&mut writer, &header)?;
+
+    let mut records = 0;
+    for (entity, sql) in ENTITY_ORDER {
+        records += write_entity(conn, &mut writer, entity, sql)?;
+        writer.flush().map_err(|err| err.to_string())?;
+    }
+
+    Ok(JsonlExportSummary {
+        path: path.to_string_lossy().to_string(),
+        schema_version,
+        records,
+    })
+}
+
+fn write_entity(
+    conn: &Connection,
+    writer: &mut BufWriter<File>,
+    entity: &str,
+    sql: &str,
+) -> Result<usize, String> {
+    let mut stmt = conn.prepare(sql).map_err(|err| err.to_string())?;
+    let columns: Vec<String> = stmt
+        .column_names()
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+    let mut rows = stmt.query([]).map_err(|err| err.to_string())?;
+
+    let mut written = 0;
+    while let Some(row) = rows.next().map_err(|err| err.to_string())? {
+        let mut data = Map::new();
+        for (index, column) in columns.iter().enumerate() {
+            let value = row.get_ref(index).map_err(|err| err.to_string())?;
+            data.insert(column.clone(), column_value(value));
+        }
+        write_line(writer, &json!({ "type": entity, "data": data }))?;
+        written += 1;
+        if written % FLUSH_EVERY == 0 {
+            writer.flush().map_err(|err| err.to_string())?;
+        }
+    }
+    Ok(written)
+}
+
+fn column_value(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(number) => json!(number),
+        ValueRef::Real(number) => json!(number),
+        ValueRef::Text(text) => Value::String(String::from_utf8_lossy(text).to_string()),
+        ValueRef::Blob(bytes) => json!(bytes),
+    }
+}
+
+fn write_line(writer: &mut BufWriter<File>, value: &Value) -> Result<(), String> {
+    serde_json::to_writer(&mut *writer, value).map_err(|err| err.to_string())?;
+    writer.write_all(b"\n").map_err(|err| err.to_string())
+}
+
+/// Renders tasks as an iCalendar document. Tasks with a timed deadline become events at the
+/// deadline; everything else is an all-day event on its target date.
+pub fn tasks_to_ics(tasks: &[Task], stamp: DateTime<Utc>) -> String {
+    let stamp = stamp.format("%Y%m%dT%H%M%SZ").to_string();
+    let mut out = String::new();
+    for line in [
+        "BEGIN:VCALENDAR",
+        "VERSION:2.0",
+        "PRODID:-//DayRally//Tasks//EN",
+        "CALSCALE:GREGORIAN",
+    ] {
+        push_ics_line(&mut out, line);
+    }
+    for task in tasks {
+        let when = match ics_event_time(task) {
+            Some(when) => when,
+            None => continue,
+        };
+        push_ics_line(&mut out, "BEGIN:VEVENT");
+        push_ics_line(&mut out, &format!("UID:{}@dayrally", task.id));
+        push_ics_line(&mut out, &format!("DTSTAMP:{}", stamp));
+        for line in when {
+            push_ics_line(&mut out, &line);
+        }
+        push_ics_line(
+            &mut out,
+            &format!("SUMMARY:{}", escape_ics_text(&task.title)),
+        );
+        if let Some(notes) = task
+            .notes
+            .as_deref()
+            .filter(|notes| !notes.trim().is_empty())
+        {
+            push_ics_line(&mut out, &format!("DESCRIPTION:{}", escape_ics_text(notes)));
+        }
+        push_ics_line(&mut out, "END:VEVENT");
+    }
+    push_ics_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+fn ics_event_time(task: &Task) -> Option<Vec<String>> {
+    if let Some(Ok(Deadline::Timestamp(at))) = task
+        .deadline_at
+        .as_deref()
+        .filter(|_| !task.deadline_all_day)
+        .map(parse_deadline)
+    {
+        let at = at.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ");
+        return Some(vec![format!("DTSTART:{}", at), format!("DTEND:{}", at)]);
+    }
+    let date = NaiveDate::parse_from_str(&task.target_date, "%Y-%m-%d").ok()?;
+    Some(vec![
+        format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")),
+        format!(
+            "DTEND;VALUE=DATE:{}",
+            (date + Duration::days(1)).format("%Y%m%d")
+        ),
+    ])
+}
+
+fn escape_ics_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+/// Appends a CRLF-terminated line, folding it so no physical line exceeds the octet limit
+/// and no UTF-8 character is split.
+fn push_ics_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for ch in line.chars() {
+        if width + ch.len_utf8() > ICS_LINE_LIMIT {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(ch);
+        width += ch.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_text_values() {
+        assert_eq!(escape_ics_text("a,b;c\\d\r\ne"), "a\\,b\\;c\\\\d\\ne");
+    }
+
+    #[test]
+    fn folds_long_lines_on_character_boundaries() {
+        let mut out = String::new();
+        push_ics_line(&mut out, &format!("SUMMARY:{}", "é".repeat(60)));
+        for line in out.split("\r\n").filter(|line| !line.is_empty()) {
+            assert!(line.len() <= ICS_LINE_LIMIT);
+        }
+        let unfolded = out.replace("\r\n ", "");
+        assert_eq!(unfolded, format!("SUMMARY:{}\r\n", "é".repeat(60)));
+    }
+}
@@ -0,0 +1,161 @@
+use serde_json::Value;
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{Ipv4Addr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+pub const DEFAULT_PORT: u16 = 4983;
+
+/// How long the accept loop sleeps when no client is waiting; bounds how quickly `stop`
+/// takes effect.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Resolves a request path to a JSON body. `None` means the route does not exist.
+pub type Handler = dyn Fn(&str) -> Option<Result<Value, String>> + Send + Sync;
+
+pub struct LocalApiServer {
+    pub port: u16,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl LocalApiServer {
+    pub fn stop(mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct LocalApiState {
+    pub server: Mutex<Option<LocalApiServer>>,
+}
+
+impl LocalApiState {
+    /// Stops the running server, if any, and starts a new one when `port` is set.
+    pub fn restart(&self, port: Option<u16>, handler: Arc<Handler>) -> Result<(), String> {
+        let mut guard = self.server.lock().expect("local api mutex");
+        if let Some(server) = guard.take() {
+            server.stop();
+        }
+        if let Some(port) = port {
+            *guard = Some(start(port, handler)?);
+        }
+        Ok(())
+    }
+}
+
+/// Serves GET requests on 127.0.0.1 from a background thread, one connection at a time.
+pub fn start(port: u16, handler: Arc<Handler>) -> Result<LocalApiServer, String> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .map_err(|err| format!("Could not listen on 127.0.0.1:{}: {}", port, err))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|err| err.to_string())?;
+    let port = listener.local_addr().map_err(|err| err.to_string())?.port();
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_flag = stop.clone();
+    let thread = thread::spawn(move || {
+        while !stop_flag.load(Ordering::SeqCst) {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    let _ = handle_connection(stream, handler.as_ref());
+                }
+                // WouldBlock when nobody is connecting; other accept errors are transient.
+                Err(_) => thread::sleep(POLL_INTERVAL),
+            }
+        }
+    });
+    Ok(LocalApiServer {
+        port,
+        stop,
+        thread: Some(thread),
+    })
+}
+
+fn handle_connection(stream: TcpStream, handler: &Handler) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Drain the headers so the client sees a clean close.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && !header.trim_end().is_empty() {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let path = parts.next().unwrap_or("").split('?').next().unwrap_or("");
+    let (status, body) = if method != "GET" {
+        (
+            "405 Method Not Allowed",
+            error_body("Only GET is supported"),
+        )
+    } else {
+        match handler(path) {
+            Some(Ok(value)) => ("200 OK", value.to_string()),
+            Some(Err(err)) => ("500 Internal Server Error", error_body(&err)),
+            None => ("404 Not Found", error_body("Not found")),
+        }
+    };
+
+    let mut stream = reader.into_inner();
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+fn error_body(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn request(port: u16, request_line: &str) -> String {
+        let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+        write!(stream, "{}\r\nHost: localhost\r\n\r\n", request_line).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn serves_known_routes_and_rejects_the_rest() {
+        let handler: Arc<Handler> = Arc::new(|path: &str| match path {
+            "/today" => Some(Ok(serde_json::json!({ "ok": true }))),
+            "/broken" => Some(Err("boom".to_string())),
+            _ => None,
+        });
+        let server = start(0, handler).unwrap();
+        let port = server.port;
+
+        let ok = request(port, "GET /today?x=1 HTTP/1.1");
+        assert!(ok.starts_with("HTTP/1.1 200 OK"));
+        assert!(ok.ends_with(r#"{"ok":true}"#));
+        assert!(request(port, "GET /nope HTTP/1.1").starts_with("HTTP/1.1 404"));
+        assert!(request(port, "GET /broken HTTP/1.1").starts_with("HTTP/1.1 500"));
+        assert!(request(port, "POST /today HTTP/1.1").starts_with("HTTP/1.1 405"));
+
+        server.stop();
+        assert!(TcpStream::connect((Ipv4Addr::LOCALHOST, port)).is_err());
+    }
+}
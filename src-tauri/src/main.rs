@@ -1,19 +1,40 @@
 mod attachments;
 mod db;
+mod export;
+mod local_api;
 mod repository;
 mod services;
 mod settings;
 
+use attachments::{AttachmentContent, WorkspaceUsage};
+use db::{SchemaReport, SchemaVersion, VacuumReport};
+use export::JsonlExportSummary;
+use local_api::LocalApiState;
 use repository::{
-    Checkin, CheckinInput, CheckinPerson, CheckinPersonInput, Note, NoteAttachment, NoteFolder,
-    NoteInput, Task, TaskInput, TaskOverview,
+    ActivityItem, AgendaDay, AttachmentAudit, CadenceHealth, Checkin, CheckinAttachment,
+    CheckinInput, CheckinPerson, CheckinPersonInput, CheckinPersonSummary, CheckinWithPerson,
+    DeadlineTask, DuplicatePeople, NeglectedPerson, NextCheckin, Note, NoteAttachment, NoteFolder,
+    NoteInput, NoteStats, NoteTemplate, NoteTemplateInput, OverdueTask, ProgressPoint, Project,
+    ProjectInput, RecurrenceIssue, SeriesReset, TagCount, TagPair, TagProgress, Task, TaskDetail,
+    TaskInput, TaskOverview, TaskPatch, TaskStatusCounts, TimerSession, WeeklyReview,
+};
+use services::timer::{watch_step, TimerEntry, TimerState, WatchStep};
+use services::undo::{DeletedEntity, UndoState};
+use settings::{
+    app_now, app_today, in_quiet_hours, load_settings, notification_sound, save_settings,
+    validate_settings, Settings,
+};
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::{Path, PathBuf},
+    process::Command,
+    sync::Arc,
 };
-use services::timer::{TimerEntry, TimerState};
-use settings::{load_settings, save_settings, Settings};
-use std::{path::PathBuf, process::Command};
 use tauri::{AppHandle, Manager, State};
 use tauri_plugin_notification::NotificationExt;
 
+const ARCHIVE_DONE_AFTER_DAYS: i64 = 30;
+
 fn workspace_from_settings(app: &AppHandle) -> Result<PathBuf, String> {
     let settings = load_settings(app).map_err(|err| err)?;
     if let Some(path) = settings.workspace_path {
@@ -23,24 +44,90 @@ fn workspace_from_settings(app: &AppHandle) -> Result<PathBuf, String> {
     }
 }
 
+fn today_from_settings(app: &AppHandle) -> String {
+    let settings = load_settings(app).unwrap_or_default();
+    app_today(&settings).format("%Y-%m-%d").to_string()
+}
+
+/// The app timezone for day boundaries; `None` means the system local timezone.
+fn timezone_from_settings(app: &AppHandle) -> Option<chrono_tz::Tz> {
+    settings::configured_timezone(&load_settings(app).unwrap_or_default())
+}
+
+/// Starts, restarts or stops the local API to match `settings`.
+fn sync_local_api(app: &AppHandle, settings: &Settings) -> Result<(), String> {
+    let port = if settings.local_api_enabled {
+        Some(settings.local_api_port.unwrap_or(local_api::DEFAULT_PORT))
+    } else {
+        None
+    };
+    let handle = app.clone();
+    let handler: Arc<local_api::Handler> = Arc::new(move |path: &str| match path {
+        "/today" => Some(local_api_today(&handle)),
+        "/timers" => Some(
+            serde_json::to_value(timer_snapshot(&handle.state::<TimerState>()))
+                .map_err(|err| err.to_string()),
+        ),
+        _ => None,
+    });
+    app.state::<LocalApiState>().restart(port, handler)
+}
+
+/// Read-only: unlike `list_task_overview`, this never rolls tasks over.
+fn local_api_today(app: &AppHandle) -> Result<serde_json::Value, String> {
+    let workspace = workspace_from_settings(app)?;
+    let conn = db::open_db(&workspace)?;
+    let overview = repository::list_overview(&conn, &today_from_settings(app), chrono::Utc::now())?;
+    serde_json::to_value(overview).map_err(|err| err.to_string())
+}
+
 #[tauri::command]
 fn get_settings(app: AppHandle) -> Result<Settings, String> {
     load_settings(&app)
 }
 
 #[tauri::command]
-fn set_workspace(app: AppHandle, path: String) -> Result<Settings, String> {
-    let workspace = PathBuf::from(path);
-    db::ensure_workspace(&workspace)?;
-    let _ = db::open_db(&workspace)?;
-
-    let settings = Settings {
-        workspace_path: Some(workspace.to_string_lossy().to_string()),
-    };
+fn update_settings(app: AppHandle, settings: Settings) -> Result<Settings, String> {
+    let settings = validate_settings(settings)?;
+    if let Some(path) = settings.workspace_path.as_deref() {
+        let workspace = PathBuf::from(path);
+        db::ensure_workspace(&workspace)?;
+        let _ = db::open_db(&workspace)?;
+    }
+    let previous = load_settings(&app).unwrap_or_default();
+    if previous.local_api_enabled != settings.local_api_enabled
+        || previous.local_api_port != settings.local_api_port
+    {
+        sync_local_api(&app, &settings)?;
+    }
     save_settings(&app, &settings)?;
+    if previous.workspace_path != settings.workspace_path {
+        app.state::<UndoState>().clear();
+    }
     Ok(settings)
 }
 
+#[tauri::command]
+fn set_workspace(app: AppHandle, path: String) -> Result<Settings, String> {
+    let mut settings = load_settings(&app)?;
+    settings.workspace_path = Some(path);
+    update_settings(app, settings)
+}
+
+#[tauri::command]
+fn set_timezone(app: AppHandle, timezone: Option<String>) -> Result<Settings, String> {
+    let mut settings = load_settings(&app)?;
+    settings.timezone = timezone;
+    update_settings(app, settings)
+}
+
+#[tauri::command]
+fn set_single_timer(app: AppHandle, enabled: bool) -> Result<Settings, String> {
+    let mut settings = load_settings(&app)?;
+    settings.single_timer = enabled;
+    update_settings(app, settings)
+}
+
 #[tauri::command]
 fn open_workspace(path: String) -> Result<(), String> {
     Command::new("open")
@@ -50,6 +137,47 @@ fn open_workspace(path: String) -> Result<(), String> {
     Ok(())
 }
 
+#[tauri::command]
+fn workspace_usage(app: AppHandle) -> Result<WorkspaceUsage, String> {
+    let workspace = workspace_from_settings(&app)?;
+    attachments::workspace_usage(&workspace)
+}
+
+#[tauri::command]
+fn export_workspace_jsonl(app: AppHandle, path: String) -> Result<JsonlExportSummary, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    export::export_workspace_jsonl(&conn, &PathBuf::from(path))
+}
+
+#[tauri::command]
+fn vacuum_database(app: AppHandle) -> Result<VacuumReport, String> {
+    let workspace = workspace_from_settings(&app)?;
+    db::vacuum(&workspace)
+}
+
+#[tauri::command]
+fn schema_version(app: AppHandle) -> Result<SchemaVersion, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db_readonly(&workspace)?;
+    db::schema_version(&conn)
+}
+
+#[tauri::command]
+fn verify_schema(app: AppHandle) -> Result<SchemaReport, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db_readonly(&workspace)?;
+    db::verify_schema(&conn)
+}
+
+fn show_notification(app: &AppHandle, body: &str, sound: Option<&str>) -> Result<(), String> {
+    let mut builder = app.notification().builder().title("DayRally").body(body);
+    if let Some(sound) = sound {
+        builder = builder.sound(sound);
+    }
+    builder.show().map_err(|err| err.to_string())
+}
+
 #[tauri::command]
 fn test_notification(app: AppHandle) -> Result<(), String> {
     app.notification()
@@ -107,374 +235,1537 @@ fn copy_text_native(text: String) -> Result<(), String> {
 fn list_task_overview(app: AppHandle) -> Result<TaskOverview, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    repository::rollover_tasks(&conn)?;
-    repository::ensure_recurrences(&conn)?;
-    repository::list_overview(&conn)
+    let settings = load_settings(&app).unwrap_or_default();
+    let today = app_today(&settings).format("%Y-%m-%d").to_string();
+    repository::rollover_tasks(
+        &conn,
+        &today,
+        settings::rollover_mode(&settings),
+        settings.log_rollovers,
+    )?;
+    repository::ensure_recurrences(&conn, &today)?;
+    repository::list_overview(&conn, &today, chrono::Utc::now())
 }
 
 #[tauri::command]
-fn list_tags(app: AppHandle) -> Result<Vec<String>, String> {
+fn agenda(app: AppHandle, days: i64) -> Result<Vec<AgendaDay>, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    repository::list_tags(&conn)
+    let settings = load_settings(&app).unwrap_or_default();
+    let today = app_today(&settings).format("%Y-%m-%d").to_string();
+    repository::rollover_tasks(
+        &conn,
+        &today,
+        settings::rollover_mode(&settings),
+        settings.log_rollovers,
+    )?;
+    repository::ensure_recurrences(&conn, &today)?;
+    repository::agenda(&conn, &today, days)
 }
 
 #[tauri::command]
-fn create_task(app: AppHandle, input: TaskInput) -> Result<Task, String> {
+fn tasks_by_deadline(app: AppHandle) -> Result<Vec<DeadlineTask>, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    repository::create_task(&conn, input)
+    let settings = load_settings(&app).unwrap_or_default();
+    repository::tasks_by_deadline(&conn, app_now(&settings))
 }
 
 #[tauri::command]
-fn update_task(app: AppHandle, id: String, input: TaskInput) -> Result<Task, String> {
+fn list_tasks_between(
+    app: AppHandle,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<Task>, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    repository::update_task(&conn, &id, input)
+    repository::tasks_in_range(&conn, &start_date, &end_date)
 }
 
 #[tauri::command]
-fn update_task_status(app: AppHandle, id: String, status: String) -> Result<Task, String> {
+fn completion_by_hour(
+    app: AppHandle,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> Result<Vec<i64>, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    repository::update_status(&conn, &id, &status)
+    let end_date = end_date.unwrap_or_else(|| today_from_settings(&app));
+    repository::completion_by_hour(
+        &conn,
+        start_date.as_deref(),
+        &end_date,
+        timezone_from_settings(&app),
+    )
 }
 
 #[tauri::command]
-fn delete_task(app: AppHandle, state: State<'_, TimerState>, id: String) -> Result<(), String> {
+fn completed_between(
+    app: AppHandle,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<Task>, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    repository::delete_task(&conn, &id)?;
-    state.remove(&id);
-    Ok(())
+    repository::completed_between(&conn, &start_date, &end_date, timezone_from_settings(&app))
 }
 
 #[tauri::command]
-fn move_task(app: AppHandle, id: String, direction: String) -> Result<(), String> {
+fn weekly_review(app: AppHandle, week_start: String) -> Result<WeeklyReview, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    repository::move_task(&conn, &id, &direction)
+    repository::weekly_review(&conn, &week_start, timezone_from_settings(&app))
 }
 
 #[tauri::command]
-fn reorder_tasks(app: AppHandle, task_ids: Vec<String>) -> Result<(), String> {
+fn checkins_scheduled_for(
+    app: AppHandle,
+    date: Option<String>,
+) -> Result<Vec<CheckinWithPerson>, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    repository::reorder_tasks(&conn, &task_ids)
+    let date = date.unwrap_or_else(|| today_from_settings(&app));
+    repository::checkins_scheduled_for(&conn, &date)
 }
 
 #[tauri::command]
-fn start_task_timer(
-    app: AppHandle,
-    state: State<'_, TimerState>,
-    task_id: String,
-) -> Result<(), String> {
+fn next_checkin(app: AppHandle) -> Result<Option<NextCheckin>, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    let task = repository::get_task(&conn, &task_id)?;
-    let minutes = task.timer_minutes.unwrap_or(25).max(1);
-    let now = chrono::Local::now();
-    let ends_at = now + chrono::Duration::minutes(minutes as i64);
-    repository::start_timer(&conn, &task_id, &ends_at.to_rfc3339())?;
-    state.upsert(TimerEntry {
-        task_id: task_id.clone(),
-        title: task.title.clone(),
-        ends_at,
-    });
+    repository::next_checkin(&conn, chrono::Local::now())
+}
 
-    let app_handle = app.clone();
-    let title = task.title.clone();
-    tauri::async_runtime::spawn(async move {
-        let remaining = ends_at - chrono::Local::now();
-        if remaining.num_seconds() > 0 {
-            tokio::time::sleep(std::time::Duration::from_secs(
-                remaining.num_seconds() as u64
-            ))
-            .await;
-        }
-        let still_running = app_handle
-            .state::<TimerState>()
-            .get(&task_id)
-            .map(|entry| entry.ends_at.timestamp() == ends_at.timestamp())
-            .unwrap_or(false);
-        if !still_running {
-            return;
-        }
+#[tauri::command]
+fn tag_progress(app: AppHandle, tag: String) -> Result<TagProgress, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::tag_progress(&conn, &tag)
+}
 
-        app_handle.state::<TimerState>().remove(&task_id);
-        if let Ok(workspace) = workspace_from_settings(&app_handle) {
-            if let Ok(conn) = db::open_db(&workspace) {
-                let _ = repository::finish_timer(&conn, &task_id);
-            }
-        }
-        if let Err(err) = app_handle
-            .notification()
-            .builder()
-            .title("DayRally")
-            .body(&format!("Time finished: {}", title))
-            .sound("default")
-            .show()
-        {
-            eprintln!("failed to show timer completion notification: {}", err);
-        }
-    });
+#[tauri::command]
+fn recent_activity(app: AppHandle, limit: i64) -> Result<Vec<ActivityItem>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::recent_activity(&conn, limit)
+}
 
-    Ok(())
+#[tauri::command]
+fn active_dates(app: AppHandle, start: String, end: String) -> Result<Vec<String>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::active_dates(&conn, &start, &end, timezone_from_settings(&app))
 }
 
 #[tauri::command]
-fn stop_task_timer(
+fn status_counts(app: AppHandle, date: Option<String>) -> Result<TaskStatusCounts, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    let date = date
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| today_from_settings(&app));
+    repository::status_counts(&conn, &date)
+}
+
+#[tauri::command]
+fn list_tags(app: AppHandle) -> Result<Vec<String>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::list_tags(&conn)
+}
+
+#[tauri::command]
+fn tag_cooccurrence(app: AppHandle, min_count: Option<i64>) -> Result<Vec<TagPair>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::tag_cooccurrence(&conn, min_count.unwrap_or(1))
+}
+
+#[tauri::command]
+fn note_tags_with_counts(app: AppHandle) -> Result<Vec<TagCount>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::note_tags_with_counts(&conn)
+}
+
+#[tauri::command]
+fn suggest_tags(app: AppHandle, query: String, limit: usize) -> Result<Vec<String>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::suggest_tags(&conn, &query, limit)
+}
+
+#[tauri::command]
+fn create_task(app: AppHandle, input: TaskInput) -> Result<Task, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::create_task(&conn, input)
+}
+
+#[tauri::command]
+fn update_task(
+    app: AppHandle,
+    id: String,
+    input: TaskInput,
+    expected_updated_at: Option<String>,
+) -> Result<Task, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::update_task(
+        &conn,
+        &id,
+        input,
+        expected_updated_at.as_deref(),
+        &today_from_settings(&app),
+    )
+}
+
+#[tauri::command]
+fn update_task_status(
     app: AppHandle,
     state: State<'_, TimerState>,
-    task_id: String,
-) -> Result<(), String> {
+    id: String,
+    status: String,
+    force: Option<bool>,
+) -> Result<Task, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    let task = repository::get_task(&conn, &task_id)?;
-    repository::stop_timer(&conn, &task_id)?;
-    state.remove(&task_id);
-    if let Err(err) = app
-        .notification()
-        .builder()
-        .title("DayRally")
-        .body(&format!("Timer stopped: {}", task.title))
-        .sound("default")
-        .show()
-    {
-        eprintln!("failed to show timer stop notification: {}", err);
+    if status == "done" && !force.unwrap_or(false) {
+        repository::ensure_unblocked(&conn, &id)?;
     }
-    Ok(())
+    let previous = repository::get_task(&conn, &id)?.status;
+    let task = repository::update_status(&conn, &id, &status)?;
+    after_status_change(&app, &state, &conn, &previous, task)
 }
 
-#[tauri::command]
-fn list_timers(state: State<'_, TimerState>) -> Result<Vec<(String, i64)>, String> {
-    let now = chrono::Local::now();
-    let timers = state
-        .list()
-        .into_iter()
-        .map(|entry| {
-            let remaining = entry.ends_at - now;
-            (entry.task_id, remaining.num_seconds().max(0))
-        })
-        .collect();
-    Ok(timers)
+/// Starts the task's timer when it has just moved to "in_progress" and the
+/// `auto_timer_on_in_progress` setting is on. Shared by `update_task_status` and `patch_task`.
+fn after_status_change(
+    app: &AppHandle,
+    state: &TimerState,
+    conn: &rusqlite::Connection,
+    previous: &str,
+    task: Task,
+) -> Result<Task, String> {
+    let auto_start = task.status == "in_progress"
+        && previous != "in_progress"
+        && task.timer_enabled
+        && state.get(&task.id).is_none()
+        && load_settings(app)
+            .unwrap_or_default()
+            .auto_timer_on_in_progress;
+    if !auto_start {
+        return Ok(task);
+    }
+    begin_timer(app, state, conn, &task)?;
+    repository::get_task(conn, &task.id)
 }
 
 #[tauri::command]
-fn list_checkin_people(app: AppHandle) -> Result<Vec<CheckinPerson>, String> {
+fn snooze_task(app: AppHandle, id: String, until: String) -> Result<Task, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    repository::list_checkin_people(&conn)
+    repository::snooze_task(&conn, &id, &until, chrono::Utc::now())
 }
 
 #[tauri::command]
-fn create_checkin_person(
-    app: AppHandle,
-    input: CheckinPersonInput,
-) -> Result<CheckinPerson, String> {
+fn task_progress_history(app: AppHandle, task_id: String) -> Result<Vec<ProgressPoint>, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    repository::create_checkin_person(&conn, input)
+    repository::task_progress_history(&conn, &task_id)
 }
 
 #[tauri::command]
-fn delete_checkin_person(app: AppHandle, id: String) -> Result<(), String> {
+fn recurring_week_preview(
+    app: AppHandle,
+    week_start: String,
+) -> Result<BTreeMap<String, Vec<String>>, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    repository::delete_checkin_person(&conn, &id)
+    repository::recurring_week_preview(&conn, &week_start)
 }
 
 #[tauri::command]
-fn list_checkins(app: AppHandle, person_id: Option<String>) -> Result<Vec<Checkin>, String> {
+fn validate_recurrence(input: TaskInput) -> Result<Vec<RecurrenceIssue>, String> {
+    Ok(repository::validate_recurrence(&input))
+}
+
+#[tauri::command]
+fn quick_add_task(app: AppHandle, title: String) -> Result<Task, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    repository::list_checkins(&conn, person_id.as_deref())
+    repository::quick_add_task(&conn, &title, &today_from_settings(&app))
 }
 
 #[tauri::command]
-fn create_checkin(app: AppHandle, input: CheckinInput) -> Result<Checkin, String> {
+fn import_markdown_tasks(
+    app: AppHandle,
+    markdown: String,
+    target_date: String,
+) -> Result<Vec<Task>, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    repository::create_checkin(&conn, input)
+    repository::import_markdown_tasks(&conn, &markdown, &target_date)
 }
 
 #[tauri::command]
-fn update_checkin(app: AppHandle, id: String, input: CheckinInput) -> Result<Checkin, String> {
+fn recurring_history(app: AppHandle, task_id: String) -> Result<Vec<Task>, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    repository::update_checkin(&conn, &id, input)
+    repository::recurring_history(&conn, &task_id)
 }
 
 #[tauri::command]
-fn delete_checkin(app: AppHandle, id: String) -> Result<(), String> {
+fn patch_task(
+    app: AppHandle,
+    state: State<'_, TimerState>,
+    id: String,
+    changes: TaskPatch,
+    force: Option<bool>,
+) -> Result<Task, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    repository::delete_checkin(&conn, &id)
+    if changes.status.as_deref() == Some("done") && !force.unwrap_or(false) {
+        repository::ensure_unblocked(&conn, &id)?;
+    }
+    let previous = repository::get_task(&conn, &id)?.status;
+    let task = repository::patch_task(&conn, &id, changes)?;
+    after_status_change(&app, &state, &conn, &previous, task)
 }
 
 #[tauri::command]
-fn list_notes(app: AppHandle) -> Result<Vec<Note>, String> {
+fn reset_recurrence_series(app: AppHandle, task_id: String) -> Result<SeriesReset, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    repository::list_notes(&conn)
+    repository::reset_recurrence_series(&conn, &task_id, &today_from_settings(&app))
 }
 
 #[tauri::command]
-fn list_note_folders(app: AppHandle) -> Result<Vec<NoteFolder>, String> {
+fn bulk_add_tag(app: AppHandle, task_ids: Vec<String>, tag: String) -> Result<usize, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    repository::list_note_folders(&conn)
+    repository::bulk_add_tag(&conn, &task_ids, &tag)
 }
 
 #[tauri::command]
-fn create_note_folder(app: AppHandle, name: String) -> Result<NoteFolder, String> {
+fn bulk_remove_tag(app: AppHandle, task_ids: Vec<String>, tag: String) -> Result<usize, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    repository::create_note_folder(&conn, &name)
+    repository::bulk_remove_tag(&conn, &task_ids, &tag)
 }
 
 #[tauri::command]
-fn delete_note_folder(app: AppHandle, folder_id: String) -> Result<(), String> {
+fn set_recurrence_paused(app: AppHandle, id: String, paused: bool) -> Result<Task, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    repository::delete_note_folder(&conn, &folder_id)
+    repository::set_recurrence_paused(&conn, &id, paused, &today_from_settings(&app))
 }
 
 #[tauri::command]
-fn create_note(app: AppHandle, input: NoteInput) -> Result<Note, String> {
+fn task_detail(app: AppHandle, id: String) -> Result<TaskDetail, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    repository::create_note(&conn, input)
+    repository::task_detail(&conn, &id)
 }
 
 #[tauri::command]
-fn update_note(app: AppHandle, id: String, input: NoteInput) -> Result<Note, String> {
+fn list_task_dependencies(app: AppHandle, task_id: String) -> Result<Vec<Task>, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    repository::update_note(&conn, &id, input)
+    repository::list_task_dependencies(&conn, &task_id)
 }
 
 #[tauri::command]
-fn delete_note(app: AppHandle, id: String) -> Result<(), String> {
+fn add_task_dependency(
+    app: AppHandle,
+    task_id: String,
+    depends_on_id: String,
+) -> Result<Vec<Task>, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    repository::delete_note(&conn, &id)
+    repository::add_task_dependency(&conn, &task_id, &depends_on_id)
 }
 
 #[tauri::command]
-fn list_note_attachments(app: AppHandle, note_id: String) -> Result<Vec<NoteAttachment>, String> {
+fn remove_task_dependency(
+    app: AppHandle,
+    task_id: String,
+    depends_on_id: String,
+) -> Result<Vec<Task>, String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    repository::list_note_attachments(&conn, &note_id)
+    repository::remove_task_dependency(&conn, &task_id, &depends_on_id)
 }
 
 #[tauri::command]
-fn save_note_attachment(
+fn delete_task(
     app: AppHandle,
-    note_id: String,
-    bytes: Vec<u8>,
-) -> Result<NoteAttachment, String> {
+    state: State<'_, TimerState>,
+    undo: State<'_, UndoState>,
+    id: String,
+) -> Result<(), String> {
     let workspace = workspace_from_settings(&app)?;
     let conn = db::open_db(&workspace)?;
-    let _ = repository::get_note(&conn, &note_id)?;
-    let saved = attachments::save_note_image(&workspace, &note_id, &bytes)?;
-    repository::create_note_attachment(&conn, &note_id, &saved.filename, &saved.path_relative)
+    let task = repository::get_task(&conn, &id)?;
+    repository::delete_task(&conn, &id)?;
+    state.remove(&id);
+    undo.push(deleted_entity("task", &task)?);
+    Ok(())
 }
 
-fn schedule_checkin_reminders(app: AppHandle) {
-    tauri::async_runtime::spawn(async move {
-        loop {
-            if let Ok(workspace) = workspace_from_settings(&app) {
-                if let Ok(conn) = db::open_db(&workspace) {
-                    let now = chrono::Local::now();
-                    if let Ok(reminders) = repository::list_due_checkin_reminders(&conn, now) {
-                        for reminder in reminders {
-                            if let Err(err) = app
-                                .notification()
-                                .builder()
-                                .title("DayRally")
-                                .body(&format!("Check-in reminder: {}", reminder.person_name))
-                                .sound("default")
-                                .show()
-                            {
-                                eprintln!("failed to show check-in reminder: {}", err);
-                            }
-                            let _ =
-                                repository::mark_checkin_reminder_sent(&conn, &reminder.checkin_id);
-                        }
-                    }
-                }
-            }
-            tokio::time::sleep(std::time::Duration::from_secs(30)).await;
-        }
-    });
+fn deleted_entity<T: serde::Serialize>(kind: &str, value: &T) -> Result<DeletedEntity, String> {
+    Ok(DeletedEntity {
+        kind: kind.to_string(),
+        data: serde_json::to_value(value).map_err(|err| err.to_string())?,
+    })
 }
 
-fn schedule_midnight(app: AppHandle) {
-    tauri::async_runtime::spawn(async move {
-        loop {
-            let now = chrono::Local::now();
-            let tomorrow = (now + chrono::Duration::days(1)).date_naive();
-            let midnight = tomorrow.and_hms_opt(0, 0, 5).unwrap();
-            let wait = (midnight - now.naive_local()).num_seconds();
-            if wait > 0 {
-                tokio::time::sleep(std::time::Duration::from_secs(wait as u64)).await;
+/// Restores the most recently deleted task, note or check-in. Returns `None` when there
+/// is nothing to undo; on failure the entry is kept so the undo can be retried.
+#[tauri::command]
+fn undo_last_delete(
+    app: AppHandle,
+    undo: State<'_, UndoState>,
+) -> Result<Option<DeletedEntity>, String> {
+    let entity = match undo.take() {
+        Some(entity) => entity,
+        None => return Ok(None),
+    };
+    let restored: Result<DeletedEntity, String> = (|| {
+        let workspace = workspace_from_settings(&app)?;
+        let conn = db::open_db(&workspace)?;
+        let data = entity.data.clone();
+        match entity.kind.as_str() {
+            "task" => {
+                let task: Task = serde_json::from_value(data).map_err(|err| err.to_string())?;
+                deleted_entity("task", &repository::restore_task(&conn, &task)?)
             }
-            if let Ok(workspace) = workspace_from_settings(&app) {
-                if let Ok(conn) = db::open_db(&workspace) {
-                    let _ = repository::rollover_tasks(&conn);
-                    let _ = repository::ensure_recurrences(&conn);
-                }
+            "note" => {
+                let note: Note = serde_json::from_value(data).map_err(|err| err.to_string())?;
+                deleted_entity("note", &repository::restore_note(&conn, &note)?)
             }
+            "checkin" => {
+                let checkin: Checkin =
+                    serde_json::from_value(data).map_err(|err| err.to_string())?;
+                deleted_entity("checkin", &repository::restore_checkin(&conn, &checkin)?)
+            }
+            other => Err(format!("Cannot undo delete of {}", other)),
         }
-    });
+    })();
+    match restored {
+        Ok(restored) => Ok(Some(restored)),
+        Err(err) => {
+            undo.push(entity);
+            Err(err)
+        }
+    }
 }
 
-fn main() {
-    tauri::Builder::default()
+#[tauri::command]
+fn move_task(app: AppHandle, id: String, direction: String) -> Result<(), String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::move_task(&conn, &id, &direction)
+}
+
+#[tauri::command]
+fn compact_sort_orders(app: AppHandle, date: String) -> Result<usize, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::compact_sort_orders(&conn, &date)
+}
+
+#[tauri::command]
+fn reorder_tasks(app: AppHandle, task_ids: Vec<String>) -> Result<(), String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::reorder_tasks(&conn, &task_ids)
+}
+
+#[tauri::command]
+fn overdue_report(app: AppHandle) -> Result<Vec<OverdueTask>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::overdue_report(&conn, &today_from_settings(&app))
+}
+
+#[tauri::command]
+fn most_deferred_tasks(app: AppHandle, limit: Option<i64>) -> Result<Vec<Task>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::most_deferred_tasks(&conn, limit.unwrap_or(10))
+}
+
+#[tauri::command]
+fn clear_rollover(app: AppHandle, task_id: String) -> Result<Task, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::clear_rollover(&conn, &task_id, &today_from_settings(&app))
+}
+
+#[tauri::command]
+fn reschedule_task(app: AppHandle, task_id: String, target_date: String) -> Result<Task, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::reschedule_task(&conn, &task_id, &target_date)
+}
+
+#[tauri::command]
+fn defer_task(app: AppHandle, task_id: String, days: Option<i64>) -> Result<Task, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::defer_task(&conn, &task_id, days.unwrap_or(1))
+}
+
+#[tauri::command]
+fn clear_done(
+    app: AppHandle,
+    state: State<'_, TimerState>,
+    date: Option<String>,
+    mode: Option<String>,
+) -> Result<usize, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    let date = date.unwrap_or_else(|| today_from_settings(&app));
+    let ids = repository::clear_done(&conn, &date, mode.as_deref())?;
+    for id in &ids {
+        state.remove(id);
+    }
+    Ok(ids.len())
+}
+
+#[tauri::command]
+fn delete_done_before(
+    app: AppHandle,
+    state: State<'_, TimerState>,
+    date: String,
+    mode: Option<String>,
+) -> Result<usize, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    let ids = repository::delete_done_before(&conn, &date, mode.as_deref())?;
+    for id in &ids {
+        state.remove(id);
+    }
+    Ok(ids.len())
+}
+
+#[tauri::command]
+fn defer_unfinished(app: AppHandle, from_date: String, to_date: String) -> Result<usize, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::defer_unfinished(&conn, &from_date, &to_date)
+}
+
+#[tauri::command]
+fn list_inbox(app: AppHandle) -> Result<Vec<Task>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::list_inbox(&conn)
+}
+
+#[tauri::command]
+fn schedule_inbox_task(app: AppHandle, id: String, date: String) -> Result<Task, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::schedule_inbox_task(&conn, &id, &date)
+}
+
+#[tauri::command]
+fn list_archived_tasks(
+    app: AppHandle,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<Task>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::list_archived_tasks(&conn, &start_date, &end_date)
+}
+
+#[tauri::command]
+fn unarchive_task(app: AppHandle, task_id: String) -> Result<Task, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::unarchive_task(&conn, &task_id)
+}
+
+#[tauri::command]
+fn start_task_timer(
+    app: AppHandle,
+    state: State<'_, TimerState>,
+    task_id: String,
+) -> Result<Option<String>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    let task = repository::get_task(&conn, &task_id)?;
+    begin_timer(&app, &state, &conn, &task)
+}
+
+/// Starts `task`'s timer and its watcher. Returns the task whose timer was stopped to
+/// honor the single-timer setting, if any.
+fn begin_timer(
+    app: &AppHandle,
+    state: &TimerState,
+    conn: &rusqlite::Connection,
+    task: &Task,
+) -> Result<Option<String>, String> {
+    let mut stopped_task_id = None;
+    if load_settings(app)?.single_timer {
+        for entry in state.list() {
+            if entry.task_id == task.id {
+                continue;
+            }
+            repository::stop_timer(conn, &entry.task_id)?;
+            state.remove(&entry.task_id);
+            stopped_task_id = Some(entry.task_id);
+        }
+    }
+
+    let seconds = task
+        .timer_seconds
+        .unwrap_or(task.timer_minutes.unwrap_or(25) as i64 * 60)
+        .max(1);
+    let now = chrono::Local::now();
+    let ends_at = now + chrono::Duration::seconds(seconds);
+    repository::start_timer(conn, &task.id, &ends_at.to_rfc3339())?;
+    state.upsert(TimerEntry {
+        task_id: task.id.clone(),
+        title: task.title.clone(),
+        ends_at,
+    });
+    watch_timer(app.clone(), task.id.clone(), task.title.clone(), ends_at);
+
+    Ok(stopped_task_id)
+}
+
+#[tauri::command]
+fn extend_task_timer(
+    app: AppHandle,
+    state: State<'_, TimerState>,
+    task_id: String,
+    minutes: i64,
+) -> Result<(), String> {
+    if !(1..=240).contains(&minutes) {
+        return Err("Minutes must be between 1 and 240".to_string());
+    }
+    let entry = state
+        .get(&task_id)
+        .ok_or_else(|| "No timer is running for this task".to_string())?;
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+
+    // The new end time changes the timestamp, so the previous watcher sees a mismatch and exits.
+    let ends_at = entry.ends_at + chrono::Duration::minutes(minutes);
+    repository::start_timer(&conn, &task_id, &ends_at.to_rfc3339())?;
+    state.upsert(TimerEntry {
+        task_id: task_id.clone(),
+        title: entry.title.clone(),
+        ends_at,
+    });
+    watch_timer(app, task_id, entry.title, ends_at);
+    Ok(())
+}
+
+fn watch_timer(
+    app_handle: AppHandle,
+    task_id: String,
+    title: String,
+    ends_at: chrono::DateTime<chrono::Local>,
+) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let entry = app_handle.state::<TimerState>().get(&task_id);
+            match watch_step(entry.as_ref(), ends_at, chrono::Local::now()) {
+                WatchStep::Sleep(duration) => tokio::time::sleep(duration).await,
+                WatchStep::Finish => break,
+                WatchStep::Abandon => return,
+            }
+        }
+
+        app_handle.state::<TimerState>().remove(&task_id);
+        if let Ok(workspace) = workspace_from_settings(&app_handle) {
+            if let Ok(conn) = db::open_db(&workspace) {
+                let _ = repository::finish_timer(&conn, &task_id);
+            }
+        }
+        // The timer is finished either way; only the notification waits out quiet hours.
+        let mut settings = load_settings(&app_handle).unwrap_or_default();
+        while in_quiet_hours(chrono::Utc::now(), &settings) {
+            tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+            settings = load_settings(&app_handle).unwrap_or_default();
+        }
+        if let Err(err) = show_notification(
+            &app_handle,
+            &format!("Time finished: {}", title),
+            notification_sound(&settings.timer_sound),
+        ) {
+            eprintln!("failed to show timer completion notification: {}", err);
+        }
+    });
+}
+
+#[tauri::command]
+fn stop_task_timer(
+    app: AppHandle,
+    state: State<'_, TimerState>,
+    task_id: String,
+) -> Result<(), String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    let task = repository::get_task(&conn, &task_id)?;
+    repository::stop_timer(&conn, &task_id)?;
+    state.remove(&task_id);
+    let settings = load_settings(&app).unwrap_or_default();
+    if let Err(err) = show_notification(
+        &app,
+        &format!("Timer stopped: {}", task.title),
+        notification_sound(&settings.timer_sound),
+    ) {
+        eprintln!("failed to show timer stop notification: {}", err);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn stop_all_timers(app: AppHandle, state: State<'_, TimerState>) -> Result<usize, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    let entries = state.list();
+    for entry in &entries {
+        repository::stop_timer(&conn, &entry.task_id)?;
+        state.remove(&entry.task_id);
+    }
+    if !entries.is_empty() {
+        let body = if entries.len() == 1 {
+            "Stopped 1 timer".to_string()
+        } else {
+            format!("Stopped {} timers", entries.len())
+        };
+        let settings = load_settings(&app).unwrap_or_default();
+        if let Err(err) = show_notification(&app, &body, notification_sound(&settings.timer_sound))
+        {
+            eprintln!("failed to show timer stop notification: {}", err);
+        }
+    }
+    Ok(entries.len())
+}
+
+#[tauri::command]
+fn list_timer_sessions(app: AppHandle, task_id: String) -> Result<Vec<TimerSession>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::list_timer_sessions(&conn, &task_id)
+}
+
+#[tauri::command]
+fn list_timers(state: State<'_, TimerState>) -> Result<Vec<(String, i64)>, String> {
+    Ok(timer_snapshot(&state))
+}
+
+/// The running timer for one task as (task id, seconds remaining, timer state).
+#[tauri::command]
+fn get_timer(
+    app: AppHandle,
+    state: State<'_, TimerState>,
+    task_id: String,
+) -> Result<Option<(String, i64, String)>, String> {
+    let entry = match state.get(&task_id) {
+        Some(entry) => entry,
+        None => return Ok(None),
+    };
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    let task = repository::get_task(&conn, &task_id)?;
+    let timer_state = task.timer_state.unwrap_or_else(|| "running".to_string());
+    let remaining = (entry.ends_at - chrono::Local::now()).num_seconds().max(0);
+    Ok(Some((entry.task_id, remaining, timer_state)))
+}
+
+/// Running timers as (task id, seconds remaining).
+fn timer_snapshot(state: &TimerState) -> Vec<(String, i64)> {
+    let now = chrono::Local::now();
+    state
+        .list()
+        .into_iter()
+        .map(|entry| {
+            let remaining = entry.ends_at - now;
+            (entry.task_id, remaining.num_seconds().max(0))
+        })
+        .collect()
+}
+
+#[tauri::command]
+fn list_checkin_people(app: AppHandle) -> Result<Vec<CheckinPerson>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::list_checkin_people(&conn)
+}
+
+#[tauri::command]
+fn create_checkin_person(
+    app: AppHandle,
+    input: CheckinPersonInput,
+) -> Result<CheckinPerson, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::create_checkin_person(&conn, input)
+}
+
+#[tauri::command]
+fn promote_action_item_to_task(
+    app: AppHandle,
+    checkin_id: String,
+    line_text: String,
+    target_date: String,
+) -> Result<Task, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::promote_action_item_to_task(&conn, &checkin_id, &line_text, &target_date)
+}
+
+#[tauri::command]
+fn acknowledge_checkin_reminder(app: AppHandle, checkin_id: String) -> Result<Checkin, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::acknowledge_checkin_reminder(&conn, &checkin_id)
+}
+
+#[tauri::command]
+fn checkins_without_next(app: AppHandle) -> Result<Vec<CheckinWithPerson>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::checkins_without_next(&conn)
+}
+
+#[tauri::command]
+fn update_checkin_person(
+    app: AppHandle,
+    id: String,
+    input: CheckinPersonInput,
+) -> Result<CheckinPerson, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::update_checkin_person(&conn, &id, input)
+}
+
+#[tauri::command]
+fn set_checkin_cadence(
+    app: AppHandle,
+    person_id: String,
+    interval: Option<i32>,
+    unit: Option<String>,
+) -> Result<CheckinPerson, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::set_checkin_cadence(&conn, &person_id, interval, unit.as_deref())
+}
+
+#[tauri::command]
+fn delete_checkin_person(app: AppHandle, id: String) -> Result<(), String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    let checkins = repository::list_checkins(&conn, Some(&id))?;
+    repository::delete_checkin_person(&conn, &id)?;
+    for checkin in checkins {
+        attachments::remove_owner_files(&workspace, attachments::CHECKIN_CATEGORY, &checkin.id)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn find_duplicate_people(app: AppHandle) -> Result<Vec<DuplicatePeople>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::find_duplicate_people(&conn)
+}
+
+#[tauri::command]
+fn merge_checkin_people(
+    app: AppHandle,
+    keep_id: String,
+    merge_ids: Vec<String>,
+) -> Result<usize, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::merge_checkin_people(&conn, &keep_id, &merge_ids)
+}
+
+#[tauri::command]
+fn list_checkins(app: AppHandle, person_id: Option<String>) -> Result<Vec<Checkin>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::list_checkins(&conn, person_id.as_deref())
+}
+
+#[tauri::command]
+fn checkin_person_summary(
+    app: AppHandle,
+    person_id: String,
+) -> Result<CheckinPersonSummary, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::checkin_person_summary(&conn, &person_id, &today_from_settings(&app))
+}
+
+#[tauri::command]
+fn checkin_cadence_report(app: AppHandle) -> Result<Vec<CadenceHealth>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::checkin_cadence_report(&conn, &today_from_settings(&app))
+}
+
+#[tauri::command]
+fn people_needing_checkin(app: AppHandle, days: i64) -> Result<Vec<NeglectedPerson>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::people_needing_checkin(&conn, &today_from_settings(&app), days)
+}
+
+#[tauri::command]
+fn export_ics(app: AppHandle, start: String, end: String) -> Result<String, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    let tasks = repository::tasks_in_range(&conn, &start, &end)?;
+    Ok(export::tasks_to_ics(&tasks, chrono::Utc::now()))
+}
+
+#[tauri::command]
+fn search_checkins(app: AppHandle, query: String) -> Result<Vec<CheckinWithPerson>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::search_checkins(&conn, &query)
+}
+
+#[tauri::command]
+fn export_checkins_markdown(app: AppHandle, person_id: String) -> Result<String, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::export_checkins_markdown(&conn, &person_id)
+}
+
+#[tauri::command]
+fn create_checkin(app: AppHandle, input: CheckinInput) -> Result<Checkin, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::create_checkin(&conn, input)
+}
+
+#[tauri::command]
+fn create_next_checkin(app: AppHandle, person_id: String) -> Result<Checkin, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::create_next_checkin(&conn, &person_id, &today_from_settings(&app))
+}
+
+#[tauri::command]
+fn update_checkin(
+    app: AppHandle,
+    id: String,
+    input: CheckinInput,
+    expected_updated_at: Option<String>,
+) -> Result<Checkin, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::update_checkin(&conn, &id, input, expected_updated_at.as_deref())
+}
+
+#[tauri::command]
+fn delete_checkin(app: AppHandle, undo: State<'_, UndoState>, id: String) -> Result<(), String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    let checkin = repository::get_checkin(&conn, &id)?;
+    repository::delete_checkin(&conn, &id)?;
+    undo.push(deleted_entity("checkin", &checkin)?);
+    attachments::remove_owner_files(&workspace, attachments::CHECKIN_CATEGORY, &id)
+}
+
+#[tauri::command]
+fn list_checkin_attachments(
+    app: AppHandle,
+    checkin_id: String,
+) -> Result<Vec<CheckinAttachment>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::list_checkin_attachments(&conn, &checkin_id)
+}
+
+#[tauri::command]
+fn save_checkin_attachment(
+    app: AppHandle,
+    checkin_id: String,
+    bytes: Vec<u8>,
+    original_name: Option<String>,
+) -> Result<CheckinAttachment, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    let _ = repository::get_checkin(&conn, &checkin_id)?;
+    let saved = attachments::save_attachment(
+        &workspace,
+        attachments::CHECKIN_CATEGORY,
+        &checkin_id,
+        &bytes,
+        original_name.as_deref().unwrap_or("image.png"),
+    )?;
+    repository::create_checkin_attachment(
+        &conn,
+        &checkin_id,
+        &saved.filename,
+        &saved.path_relative,
+        saved.thumb_path_relative.as_deref(),
+    )
+}
+
+#[tauri::command]
+fn delete_checkin_attachment(app: AppHandle, attachment_id: String) -> Result<(), String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    let attachment = repository::get_checkin_attachment(&conn, &attachment_id)?;
+    repository::delete_checkin_attachment(&conn, &attachment_id)?;
+    attachments::remove_stored(
+        &workspace,
+        &[attachments::StoredAttachment {
+            filename: attachment.filename,
+            path_relative: attachment.path_relative,
+            thumb_path_relative: attachment.thumb_path_relative,
+        }],
+    );
+    Ok(())
+}
+
+#[tauri::command]
+fn list_notes(app: AppHandle) -> Result<Vec<Note>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::list_notes(&conn)
+}
+
+#[tauri::command]
+fn list_backlinks(app: AppHandle, note_id: String) -> Result<Vec<Note>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::list_backlinks(&conn, &note_id)
+}
+
+#[tauri::command]
+fn list_notes_for_task(app: AppHandle, task_id: String) -> Result<Vec<Note>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::list_notes_for_task(&conn, &task_id)
+}
+
+#[tauri::command]
+fn list_notes_in_folder(app: AppHandle, folder_id: Option<String>) -> Result<Vec<Note>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::list_notes_in_folder(&conn, folder_id.as_deref())
+}
+
+#[tauri::command]
+fn reorder_notes(
+    app: AppHandle,
+    folder_id: Option<String>,
+    note_ids: Vec<String>,
+) -> Result<(), String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::reorder_notes(&conn, folder_id.as_deref(), &note_ids)
+}
+
+#[tauri::command]
+fn list_note_folders(app: AppHandle) -> Result<Vec<NoteFolder>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::list_note_folders(&conn)
+}
+
+#[tauri::command]
+fn create_note_folder(app: AppHandle, name: String) -> Result<NoteFolder, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::create_note_folder(&conn, &name)
+}
+
+#[tauri::command]
+fn list_note_templates(app: AppHandle) -> Result<Vec<NoteTemplate>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::list_note_templates(&conn)
+}
+
+#[tauri::command]
+fn create_note_template(app: AppHandle, input: NoteTemplateInput) -> Result<NoteTemplate, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::create_note_template(&conn, input)
+}
+
+#[tauri::command]
+fn delete_note_template(app: AppHandle, id: String) -> Result<(), String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::delete_note_template(&conn, &id)
+}
+
+#[tauri::command]
+fn create_note_from_template(
+    app: AppHandle,
+    template_id: String,
+    title: String,
+) -> Result<Note, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::create_note_from_template(&conn, &template_id, &title, &today_from_settings(&app))
+}
+
+#[tauri::command]
+fn delete_note_folder(app: AppHandle, folder_id: String) -> Result<(), String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::delete_note_folder(&conn, &folder_id)
+}
+
+#[tauri::command]
+fn list_projects(app: AppHandle) -> Result<Vec<Project>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::list_projects(&conn)
+}
+
+#[tauri::command]
+fn create_project(app: AppHandle, input: ProjectInput) -> Result<Project, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::create_project(&conn, input)
+}
+
+#[tauri::command]
+fn update_project(app: AppHandle, id: String, input: ProjectInput) -> Result<Project, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::update_project(&conn, &id, input)
+}
+
+#[tauri::command]
+fn delete_project(app: AppHandle, id: String) -> Result<(), String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::delete_project(&conn, &id)
+}
+
+#[tauri::command]
+fn list_tasks_by_project(app: AppHandle, project_id: String) -> Result<Vec<Task>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::list_tasks_by_project(&conn, &project_id)
+}
+
+#[tauri::command]
+fn move_task_to_project(
+    app: AppHandle,
+    task_id: String,
+    project_id: Option<String>,
+) -> Result<Task, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::move_task_to_project(&conn, &task_id, project_id.as_deref())
+}
+
+#[tauri::command]
+fn create_note(app: AppHandle, input: NoteInput) -> Result<Note, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::create_note(&conn, input)
+}
+
+#[tauri::command]
+fn update_note(
+    app: AppHandle,
+    id: String,
+    input: NoteInput,
+    expected_updated_at: Option<String>,
+) -> Result<Note, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::update_note(&conn, &id, input, expected_updated_at.as_deref())
+}
+
+#[tauri::command]
+fn delete_note(app: AppHandle, undo: State<'_, UndoState>, id: String) -> Result<(), String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    let note = repository::get_note(&conn, &id)?;
+    repository::delete_note(&conn, &id)?;
+    undo.push(deleted_entity("note", &note)?);
+    Ok(())
+}
+
+#[tauri::command]
+fn move_note(app: AppHandle, note_id: String, folder_id: Option<String>) -> Result<Note, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::move_note(&conn, &note_id, folder_id.as_deref())
+}
+
+#[tauri::command]
+fn note_preview(app: AppHandle, id: String, max_chars: Option<usize>) -> Result<String, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::note_preview(&conn, &id, max_chars.unwrap_or(160))
+}
+
+#[tauri::command]
+fn note_stats(app: AppHandle, id: String) -> Result<NoteStats, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::note_stats(&conn, &id)
+}
+
+#[tauri::command]
+fn list_note_attachments(app: AppHandle, note_id: String) -> Result<Vec<NoteAttachment>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    repository::list_note_attachments(&conn, &note_id)
+}
+
+/// Enforces the per-note attachment caps from settings before new files are written.
+fn check_note_attachment_limits(
+    app: &AppHandle,
+    conn: &rusqlite::Connection,
+    workspace: &Path,
+    note_id: &str,
+    incoming: &[u64],
+) -> Result<(), String> {
+    let (max_count, max_bytes) =
+        settings::note_attachment_limits(&load_settings(app).unwrap_or_default());
+    let existing: Vec<String> = repository::list_note_attachments(conn, note_id)?
+        .into_iter()
+        .map(|attachment| attachment.path_relative)
+        .collect();
+    attachments::check_owner_limits(workspace, &existing, incoming, max_count, max_bytes)
+}
+
+#[tauri::command]
+fn save_note_attachment(
+    app: AppHandle,
+    note_id: String,
+    bytes: Vec<u8>,
+) -> Result<NoteAttachment, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    let _ = repository::get_note(&conn, &note_id)?;
+    check_note_attachment_limits(&app, &conn, &workspace, &note_id, &[bytes.len() as u64])?;
+    let saved = attachments::save_note_image(&workspace, &note_id, &bytes)?;
+    repository::create_note_attachment(
+        &conn,
+        &note_id,
+        &saved.filename,
+        &saved.path_relative,
+        saved.thumb_path_relative.as_deref(),
+    )
+}
+
+#[tauri::command]
+fn save_note_attachments(
+    app: AppHandle,
+    note_id: String,
+    files: Vec<Vec<u8>>,
+) -> Result<Vec<NoteAttachment>, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    let _ = repository::get_note(&conn, &note_id)?;
+    let sizes: Vec<u64> = files.iter().map(|bytes| bytes.len() as u64).collect();
+    check_note_attachment_limits(&app, &conn, &workspace, &note_id, &sizes)?;
+    let saved = attachments::save_note_images(&workspace, &note_id, &files)?;
+
+    let result: Result<Vec<NoteAttachment>, String> = (|| {
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|err| err.to_string())?;
+        let mut created = Vec::with_capacity(saved.len());
+        for stored in &saved {
+            created.push(repository::create_note_attachment(
+                &tx,
+                &note_id,
+                &stored.filename,
+                &stored.path_relative,
+                stored.thumb_path_relative.as_deref(),
+            )?);
+        }
+        tx.commit().map_err(|err| err.to_string())?;
+        Ok(created)
+    })();
+    if result.is_err() {
+        attachments::remove_stored(&workspace, &saved);
+    }
+    result
+}
+
+#[tauri::command]
+fn read_note_attachment(
+    app: AppHandle,
+    attachment_id: String,
+) -> Result<AttachmentContent, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    let attachment = repository::get_note_attachment(&conn, &attachment_id)?;
+    attachments::read_attachment(&workspace, &attachment.filename, &attachment.path_relative)
+}
+
+/// Cross-references `note_attachments` with the files under `attachments/`. With
+/// `repair`, rows whose file is gone are deleted and unreferenced files are moved to
+/// `attachments/_orphaned/`.
+#[tauri::command]
+fn audit_attachments(app: AppHandle, repair: Option<bool>) -> Result<AttachmentAudit, String> {
+    let workspace = workspace_from_settings(&app)?;
+    let conn = db::open_db(&workspace)?;
+    let rows = repository::list_all_note_attachments(&conn)?;
+
+    let known: HashSet<String> = rows
+        .iter()
+        .flat_map(|row| {
+            std::iter::once(row.path_relative.clone()).chain(row.thumb_path_relative.clone())
+        })
+        .collect();
+    let files_without_rows = attachments::find_orphaned_note_files(&workspace, &known)?;
+    let rows_without_files: Vec<NoteAttachment> = rows
+        .into_iter()
+        .filter(|row| !workspace.join(&row.path_relative).is_file())
+        .collect();
+
+    let repair = repair.unwrap_or(false);
+    if repair {
+        let ids: Vec<String> = rows_without_files
+            .iter()
+            .map(|row| row.id.clone())
+            .collect();
+        repository::delete_note_attachments(&conn, &ids)?;
+        for path in &files_without_rows {
+            attachments::move_to_orphaned(&workspace, path)?;
+        }
+    }
+
+    Ok(AttachmentAudit {
+        rows_without_files,
+        files_without_rows,
+        repaired: repair,
+    })
+}
+
+fn schedule_reminders(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let settings = load_settings(&app).unwrap_or_default();
+            // Nothing is marked sent during quiet hours, so due reminders fire once it ends.
+            let quiet = in_quiet_hours(chrono::Utc::now(), &settings);
+            if let (false, Ok(workspace)) = (quiet, workspace_from_settings(&app)) {
+                if let Ok(conn) = db::open_db(&workspace) {
+                    let _ = repository::clear_expired_snoozes(&conn, chrono::Utc::now());
+                    let now = chrono::Local::now();
+                    if let Ok(reminders) = repository::list_due_checkin_reminders(&conn, now) {
+                        for reminder in reminders {
+                            if let Err(err) = show_notification(
+                                &app,
+                                &format!("Check-in reminder: {}", reminder.person_name),
+                                notification_sound(&settings.checkin_reminder_sound),
+                            ) {
+                                eprintln!("failed to show check-in reminder: {}", err);
+                            }
+                            let _ =
+                                repository::mark_checkin_reminder_sent(&conn, &reminder.checkin_id);
+                        }
+                    }
+                    if let Ok(reminders) = repository::list_due_task_reminders(&conn, now) {
+                        for reminder in reminders {
+                            if let Err(err) = show_notification(
+                                &app,
+                                &format!("Reminder: {}", reminder.title),
+                                notification_sound(&settings.deadline_sound),
+                            ) {
+                                eprintln!("failed to show task reminder: {}", err);
+                            }
+                            let _ = repository::mark_task_reminder_sent(&conn, &reminder.task_id);
+                        }
+                    }
+                }
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+        }
+    });
+}
+
+fn schedule_midnight(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let wait = settings::seconds_until_next_day(&load_settings(&app).unwrap_or_default());
+            if wait > 0 {
+                tokio::time::sleep(std::time::Duration::from_secs(wait as u64)).await;
+            }
+            if let Ok(workspace) = workspace_from_settings(&app) {
+                if let Ok(conn) = db::open_db(&workspace) {
+                    let settings = load_settings(&app).unwrap_or_default();
+                    let today = app_today(&settings);
+                    let today_value = today.format("%Y-%m-%d").to_string();
+                    let _ = repository::rollover_tasks(
+                        &conn,
+                        &today_value,
+                        settings::rollover_mode(&settings),
+                        settings.log_rollovers,
+                    );
+                    let _ = repository::ensure_recurrences(&conn, &today_value);
+                    let archive_before = (today - chrono::Duration::days(ARCHIVE_DONE_AFTER_DAYS))
+                        .format("%Y-%m-%d")
+                        .to_string();
+                    let _ = repository::archive_done_before(&conn, &archive_before);
+                }
+            }
+        }
+    });
+}
+
+fn main() {
+    tauri::Builder::default()
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_dialog::init())
         .manage(TimerState::default())
+        .manage(UndoState::default())
+        .manage(LocalApiState::default())
         .setup(|app| {
             schedule_midnight(app.handle().clone());
-            schedule_checkin_reminders(app.handle().clone());
+            schedule_reminders(app.handle().clone());
+            let settings = load_settings(app.handle()).unwrap_or_default();
+            if let Err(err) = sync_local_api(app.handle(), &settings) {
+                eprintln!("failed to start local API: {}", err);
+            }
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             get_settings,
+            update_settings,
             set_workspace,
+            set_timezone,
+            set_single_timer,
             open_workspace,
+            workspace_usage,
+            export_workspace_jsonl,
+            verify_schema,
+            schema_version,
+            vacuum_database,
             test_notification,
             open_notification_settings,
             copy_text_native,
             list_task_overview,
+            agenda,
+            list_tasks_between,
+            tasks_by_deadline,
+            completed_between,
+            completion_by_hour,
+            weekly_review,
+            active_dates,
+            recent_activity,
+            tag_progress,
+            next_checkin,
+            checkins_scheduled_for,
+            status_counts,
             list_tags,
+            note_tags_with_counts,
+            suggest_tags,
+            tag_cooccurrence,
             create_task,
             update_task,
             update_task_status,
+            snooze_task,
+            patch_task,
+            validate_recurrence,
+            recurring_week_preview,
+            quick_add_task,
+            import_markdown_tasks,
+            recurring_history,
+            task_progress_history,
+            set_recurrence_paused,
+            reset_recurrence_series,
+            bulk_add_tag,
+            bulk_remove_tag,
+            list_task_dependencies,
+            task_detail,
+            add_task_dependency,
+            remove_task_dependency,
             delete_task,
+            undo_last_delete,
             move_task,
             reorder_tasks,
+            overdue_report,
+            most_deferred_tasks,
+            clear_rollover,
+            reschedule_task,
+            defer_task,
+            defer_unfinished,
+            clear_done,
+            delete_done_before,
+            compact_sort_orders,
+            list_inbox,
+            schedule_inbox_task,
+            list_archived_tasks,
+            unarchive_task,
             start_task_timer,
+            extend_task_timer,
             stop_task_timer,
+            stop_all_timers,
             list_timers,
+            get_timer,
+            list_timer_sessions,
             list_checkin_people,
             create_checkin_person,
+            update_checkin_person,
+            checkins_without_next,
+            acknowledge_checkin_reminder,
+            promote_action_item_to_task,
+            set_checkin_cadence,
             delete_checkin_person,
+            find_duplicate_people,
+            merge_checkin_people,
             list_checkins,
+            checkin_person_summary,
+            checkin_cadence_report,
+            people_needing_checkin,
+            export_checkins_markdown,
+            search_checkins,
+            export_ics,
             create_checkin,
+            create_next_checkin,
             update_checkin,
             delete_checkin,
+            list_checkin_attachments,
+            save_checkin_attachment,
+            delete_checkin_attachment,
             list_notes,
+            list_notes_for_task,
+            list_backlinks,
+            list_notes_in_folder,
+            reorder_notes,
             list_note_folders,
             create_note_folder,
             delete_note_folder,
+            list_projects,
+            create_project,
+            update_project,
+            delete_project,
+            list_tasks_by_project,
+            move_task_to_project,
+            list_note_templates,
+            create_note_template,
+            delete_note_template,
+            create_note_from_template,
             create_note,
             update_note,
             delete_note,
+            move_note,
+            note_preview,
+            note_stats,
             list_note_attachments,
-            save_note_attachment
+            save_note_attachment,
+            save_note_attachments,
+            read_note_attachment,
+            audit_attachments
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
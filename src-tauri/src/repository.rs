@@ -1,9 +1,21 @@
-use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, Utc, Weekday};
-use rusqlite::{params, Connection, OptionalExtension};
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime,
+    SecondsFormat, TimeZone, Timelike, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use rusqlite::{params, params_from_iter, types::Value, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use uuid::Uuid;
 
+use crate::services::deadline::{
+    canonical_deadline, deadline_cutoff, parse_deadline, shift_deadline, Deadline,
+};
+use crate::services::markdown::{
+    extract_wikilinks, list_item_text, parse_checklist, preview_text, strip_markdown,
+};
+use crate::services::recurrence::{cadence_rule, next_occurrence};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Task {
     pub id: String,
@@ -14,17 +26,34 @@ pub struct Task {
     pub status: String,
     pub progress_percent: i32,
     pub deadline_at: Option<String>,
+    pub deadline_all_day: bool,
     pub is_recurring: bool,
     pub recurrence_type: Option<String>,
     pub recurrence_interval: Option<i32>,
     pub recurrence_weekdays: Option<String>,
+    pub catchup_policy: String,
+    pub recurrence_paused: bool,
+    pub recurrence_reset_notes: bool,
+    pub recurrence_keep_missed: bool,
     pub timer_enabled: bool,
     pub timer_minutes: Option<i32>,
+    pub timer_seconds: Option<i64>,
     pub timer_state: Option<String>,
     pub timer_ends_at: Option<String>,
     pub rolled_over: bool,
+    /// Date the task was planned for before its first rollover; later rollovers keep it.
     pub rolled_from_date: Option<String>,
+    pub rollover_count: i64,
     pub sort_order: i64,
+    pub archived: bool,
+    pub reminder_at: Option<String>,
+    pub reminder_state: Option<String>,
+    pub done_at: Option<String>,
+    /// Check-in whose action item this task was promoted from.
+    pub source_checkin_id: Option<String>,
+    pub project_id: Option<String>,
+    /// UTC time until which the task is hidden from today's list.
+    pub snooze_until: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -35,16 +64,67 @@ pub struct TaskInput {
     pub notes: Option<String>,
     #[serde(default)]
     pub tags: Vec<String>,
-    pub target_date: String,
+    #[serde(default)]
+    pub target_date: Option<String>,
     pub status: String,
     pub progress_percent: i32,
     pub deadline_at: Option<String>,
+    #[serde(default)]
+    pub deadline_all_day: bool,
     pub is_recurring: bool,
     pub recurrence_type: Option<String>,
     pub recurrence_interval: Option<i32>,
     pub recurrence_weekdays: Option<String>,
+    /// "skip" (default) jumps a missed recurring task to its next date; "all" also
+    /// creates a task for every occurrence that was missed.
+    #[serde(default)]
+    pub catchup_policy: Option<String>,
+    /// Keeps a recurring task but stops new occurrences from being generated. Omitted on
+    /// update keeps the stored flag.
+    #[serde(default)]
+    pub recurrence_paused: Option<bool>,
+    /// New occurrences start with empty notes instead of a copy of this task's.
+    #[serde(default)]
+    pub recurrence_reset_notes: bool,
+    /// A missed occurrence stays on its own date as that day's record and a fresh
+    /// occurrence is created, instead of the missed task being moved forward.
+    #[serde(default)]
+    pub recurrence_keep_missed: bool,
     pub timer_enabled: bool,
     pub timer_minutes: Option<i32>,
+    /// Exact timer length; wins over `timer_minutes`, which stays the unit shown in the UI.
+    #[serde(default)]
+    pub timer_seconds: Option<i64>,
+    #[serde(default)]
+    pub reminder_at: Option<String>,
+    /// Omitted on update keeps the task's current project; blank removes it.
+    #[serde(default)]
+    pub project_id: Option<String>,
+}
+
+/// Fields that can be changed without sending a whole `TaskInput`. `None` leaves the
+/// stored value alone; an empty `notes` or `deadline_at` clears it and an empty
+/// `target_date` moves the task to the inbox.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct TaskPatch {
+    pub title: Option<String>,
+    pub notes: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub target_date: Option<String>,
+    pub status: Option<String>,
+    pub progress_percent: Option<i32>,
+    pub deadline_at: Option<String>,
+    pub deadline_all_day: Option<bool>,
+}
+
+/// Everything the task detail view shows, loaded in one call.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TaskDetail {
+    pub task: Task,
+    /// Tag names from `task_tags`, which wins over the `tasks.tags` CSV if they disagree.
+    pub tags: Vec<String>,
+    pub dependencies: Vec<Task>,
+    pub notes: Vec<Note>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -52,6 +132,93 @@ pub struct TaskOverview {
     pub today: Vec<Task>,
     pub rolled_over: Vec<Task>,
     pub upcoming: Vec<Task>,
+    /// Unfinished tasks left on a past date because rollover is limited or off.
+    pub overdue: Vec<Task>,
+}
+
+/// One row of the cross-entity activity feed; `kind` is "task", "note" or "checkin".
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActivityItem {
+    pub kind: String,
+    pub id: String,
+    pub title: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RecurrenceIssue {
+    /// "error" when the rule will not repeat as intended or will not save, "warning" when
+    /// it will be adjusted on save.
+    pub level: String,
+    pub field: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TagPair {
+    pub tag_a: String,
+    pub tag_b: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WeeklyReview {
+    pub week_start: String,
+    pub week_end: String,
+    pub completed: Vec<Task>,
+    pub created_count: i64,
+    pub repeatedly_rolled: Vec<Task>,
+    pub checkins_held: i64,
+    pub focused_seconds: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AgendaDay {
+    pub date: String,
+    pub tasks: Vec<Task>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TaskStatusCounts {
+    pub date: String,
+    pub counts: BTreeMap<String, i64>,
+    pub rolled_over: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TagProgress {
+    pub tag: String,
+    pub total: i64,
+    pub done: i64,
+    pub average_progress: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SeriesReset {
+    pub deleted: usize,
+    pub next_date: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OverdueTask {
+    pub task: Task,
+    pub carried_since: String,
+    pub age_days: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeadlineTask {
+    pub task: Task,
+    /// Local moment the deadline passes (`YYYY-MM-DDTHH:MM:SS`).
+    pub cutoff: String,
+    /// "overdue", "today", "this_week" (within the next 7 days) or "later".
+    pub urgency: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -61,6 +228,10 @@ pub struct Note {
     pub body_markdown: String,
     pub tags: Vec<String>,
     pub folder_id: Option<String>,
+    pub task_id: Option<String>,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    pub sort_order: i64,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -71,6 +242,37 @@ pub struct NoteInput {
     pub body_markdown: String,
     pub tags: Vec<String>,
     pub folder_id: Option<String>,
+    #[serde(default)]
+    pub task_id: Option<String>,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub icon: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NoteTemplate {
+    pub id: String,
+    pub name: String,
+    pub body_markdown: String,
+    pub tags: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NoteTemplateInput {
+    pub name: String,
+    pub body_markdown: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NoteStats {
+    pub word_count: usize,
+    pub character_count: usize,
+    pub reading_minutes: usize,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -79,9 +281,17 @@ pub struct NoteAttachment {
     pub note_id: String,
     pub filename: String,
     pub path_relative: String,
+    pub thumb_path_relative: Option<String>,
     pub created_at: String,
 }
 
+#[derive(Debug, Serialize, Clone)]
+pub struct AttachmentAudit {
+    pub rows_without_files: Vec<NoteAttachment>,
+    pub files_without_rows: Vec<String>,
+    pub repaired: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct NoteFolder {
     pub id: String,
@@ -90,19 +300,76 @@ pub struct NoteFolder {
     pub updated_at: String,
 }
 
+/// A named group of tasks; deleting it leaves its tasks without a project.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Project {
+    pub id: String,
+    pub name: String,
+    pub color: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ProjectInput {
+    pub name: String,
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProgressPoint {
+    pub percent: i32,
+    pub recorded_at: String,
+}
+
+/// One run of a task's timer. `ended_at` is NULL while the session is running;
+/// `completed` is set when it ran to the end rather than being stopped.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TimerSession {
+    pub id: String,
+    pub task_id: String,
+    pub started_at: String,
+    pub planned_seconds: i64,
+    pub ended_at: Option<String>,
+    pub completed: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CheckinAttachment {
+    pub id: String,
+    pub checkin_id: String,
+    pub filename: String,
+    pub path_relative: String,
+    pub thumb_path_relative: Option<String>,
+    pub created_at: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CheckinPerson {
     pub id: String,
     pub name: String,
     pub relationship: String,
+    pub cadence_interval: Option<i32>,
+    pub cadence_unit: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DuplicatePeople {
+    pub name: String,
+    pub people: Vec<CheckinPerson>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CheckinPersonInput {
     pub name: String,
     pub relationship: String,
+    #[serde(default)]
+    pub cadence_interval: Option<i32>,
+    #[serde(default)]
+    pub cadence_unit: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -133,6 +400,39 @@ pub struct CheckinInput {
     pub reminder_time: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CheckinPersonSummary {
+    pub person_id: String,
+    pub total_checkins: i64,
+    pub last_checkin_date: Option<String>,
+    pub next_checkin_date: Option<String>,
+    pub outstanding_action_items: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CadenceHealth {
+    pub person: CheckinPerson,
+    pub last_checkin_date: Option<String>,
+    pub days_since: Option<i64>,
+    pub due_date: Option<String>,
+    pub overdue: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NeglectedPerson {
+    pub person: CheckinPerson,
+    pub last_checkin_date: Option<String>,
+    /// `None` when there has never been a check-in.
+    pub days_since: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TaskReminder {
+    pub task_id: String,
+    pub title: String,
+    pub reminder_at: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CheckinReminder {
     pub checkin_id: String,
@@ -141,11 +441,34 @@ pub struct CheckinReminder {
     pub reminder_time: String,
 }
 
-fn today() -> String {
-    let now = Local::now();
-    format!("{:04}-{:02}-{:02}", now.year(), now.month(), now.day())
+/// A check-in together with the name of the person it was held with.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CheckinWithPerson {
+    pub checkin: Checkin,
+    pub person_name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NextCheckin {
+    pub checkin_id: String,
+    pub person_id: String,
+    pub person_name: String,
+    pub next_checkin_date: String,
+    pub reminder_time: Option<String>,
 }
 
+/// Prefix of the error returned when a task is completed before its blockers.
+/// The UI matches on it to offer a "complete anyway" confirmation.
+pub const BLOCKED_BY_DEPENDENCIES: &str = "blocked_by_dependencies";
+
+/// Returned when an update carried an `expected_updated_at` that no longer matches.
+pub const MODIFIED_ELSEWHERE: &str = "modified by another process";
+
+/// Stored `target_date` of undated (inbox) tasks.
+pub const INBOX_DATE: &str = "";
+
+const TASK_STATUSES: &[&str] = &["todo", "in_progress", "done", "skipped"];
+
 fn parse_date(value: &str) -> Result<NaiveDate, String> {
     NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|err| err.to_string())
 }
@@ -215,6 +538,131 @@ fn normalize_reminder_state(
     }
 }
 
+fn normalize_deadline(
+    deadline_at: Option<&str>,
+    all_day: bool,
+) -> Result<(Option<String>, i32), String> {
+    let value = match deadline_at.map(|value| value.trim()) {
+        Some(value) if !value.is_empty() => value,
+        _ => return Ok((None, 0)),
+    };
+    // A bare date has no meaningful time, so it is always an all-day deadline.
+    let all_day = all_day || matches!(parse_deadline(value)?, Deadline::Date(_));
+    Ok((
+        Some(canonical_deadline(value)?),
+        if all_day { 1 } else { 0 },
+    ))
+}
+
+/// Counts action item lines that have not been ticked off (`- [x]`).
+fn count_open_action_items(value: &str) -> i64 {
+    value
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .filter(|line| {
+            let lower = line.to_lowercase();
+            !(lower.starts_with("- [x]") || lower.starts_with("* [x]") || lower.starts_with("[x]"))
+        })
+        .count() as i64
+}
+
+fn normalize_task_reminder(
+    reminder_at: Option<&str>,
+    status: &str,
+    existing: Option<&Task>,
+) -> Result<(Option<String>, Option<String>), String> {
+    let reminder_at = match reminder_at.map(|value| value.trim()) {
+        Some(value) if !value.is_empty() && status != "done" => value,
+        _ => return Ok((None, None)),
+    };
+    DateTime::parse_from_rfc3339(reminder_at)
+        .map_err(|_| format!("Invalid reminder time: {}", reminder_at))?;
+    let reminder_state = match existing {
+        Some(task) if task.reminder_at.as_deref() == Some(reminder_at) => {
+            task.reminder_state.clone()
+        }
+        _ => Some("scheduled".to_string()),
+    };
+    Ok((Some(reminder_at.to_string()), reminder_state))
+}
+
+fn normalize_cadence(
+    interval: Option<i32>,
+    unit: Option<&str>,
+) -> Result<(Option<i32>, Option<String>), String> {
+    match (
+        interval,
+        unit.map(|value| value.trim())
+            .filter(|value| !value.is_empty()),
+    ) {
+        (Some(interval), Some(unit)) => {
+            let _ = cadence_rule(interval as i64, unit)?;
+            Ok((Some(interval), Some(unit.to_string())))
+        }
+        (None, None) => Ok((None, None)),
+        _ => Err("Cadence needs both an interval and a unit".to_string()),
+    }
+}
+
+fn advance_by_cadence(person: &CheckinPerson, from: &str) -> Result<Option<String>, String> {
+    let (Some(interval), Some(unit)) = (person.cadence_interval, person.cadence_unit.as_deref())
+    else {
+        return Ok(None);
+    };
+    let rule = cadence_rule(interval as i64, unit)?;
+    let next = next_occurrence(&rule, parse_date(from)?);
+    Ok(Some(next.format("%Y-%m-%d").to_string()))
+}
+
+/// Falls back to the person's cadence when no explicit next check-in date was given.
+/// Only new check-ins get the default.
+fn default_next_checkin_date(
+    conn: &Connection,
+    person_id: &str,
+    checkin_date: &str,
+    next_checkin_date: Option<String>,
+) -> Result<Option<String>, String> {
+    if let Some(next_date) = normalize_optional_text(next_checkin_date) {
+        return Ok(Some(next_date));
+    }
+    match get_checkin_person(conn, person_id) {
+        Ok(person) => advance_by_cadence(&person, checkin_date),
+        Err(_) => Ok(None),
+    }
+}
+
+fn validate_checkin_schedule(
+    checkin_date: &str,
+    next_checkin_date: Option<&str>,
+    reminder_enabled: bool,
+    reminder_time: Option<&str>,
+) -> Result<(), String> {
+    let checkin = parse_date(checkin_date)?;
+    let next_checkin_date = next_checkin_date
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty());
+    let reminder_time = reminder_time
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty());
+
+    if let Some(next_date) = next_checkin_date {
+        if parse_date(next_date)? < checkin {
+            return Err(format!(
+                "Next check-in date ({}) cannot be before the check-in date ({})",
+                next_date, checkin_date
+            ));
+        }
+    }
+    if let Some(reminder_time) = reminder_time {
+        let _ = parse_time(reminder_time)?;
+        if reminder_enabled && next_checkin_date.is_none() {
+            return Err("A reminder needs a next check-in date to fire".to_string());
+        }
+    }
+    Ok(())
+}
+
 fn map_task_row(row: &rusqlite::Row<'_>) -> Result<Task, rusqlite::Error> {
     let tags_csv: String = row.get("tags")?;
     Ok(Task {
@@ -226,17 +674,31 @@ fn map_task_row(row: &rusqlite::Row<'_>) -> Result<Task, rusqlite::Error> {
         status: row.get("status")?,
         progress_percent: row.get("progress_percent")?,
         deadline_at: row.get("deadline_at")?,
+        deadline_all_day: row.get::<_, i32>("deadline_all_day")? == 1,
         is_recurring: row.get::<_, i32>("is_recurring")? == 1,
         recurrence_type: row.get("recurrence_type")?,
         recurrence_interval: row.get("recurrence_interval")?,
         recurrence_weekdays: row.get("recurrence_weekdays")?,
+        catchup_policy: row.get("catchup_policy")?,
+        recurrence_paused: row.get::<_, i32>("recurrence_paused")? == 1,
+        recurrence_reset_notes: row.get::<_, i32>("recurrence_reset_notes")? == 1,
+        recurrence_keep_missed: row.get::<_, i32>("recurrence_keep_missed")? == 1,
         timer_enabled: row.get::<_, i32>("timer_enabled")? == 1,
         timer_minutes: row.get("timer_minutes")?,
+        timer_seconds: row.get("timer_seconds")?,
         timer_state: row.get("timer_state")?,
         timer_ends_at: row.get("timer_ends_at")?,
         rolled_over: row.get::<_, i32>("rolled_over")? == 1,
         rolled_from_date: row.get("rolled_from_date")?,
+        rollover_count: row.get("rollover_count")?,
+        done_at: row.get("done_at")?,
         sort_order: row.get("sort_order")?,
+        archived: row.get::<_, i32>("archived")? == 1,
+        reminder_at: row.get("reminder_at")?,
+        reminder_state: row.get("reminder_state")?,
+        source_checkin_id: row.get("source_checkin_id")?,
+        project_id: row.get("project_id")?,
+        snooze_until: row.get("snooze_until")?,
         created_at: row.get("created_at")?,
         updated_at: row.get("updated_at")?,
     })
@@ -250,6 +712,10 @@ fn map_note_row(row: &rusqlite::Row<'_>) -> Result<Note, rusqlite::Error> {
         body_markdown: row.get("body_markdown")?,
         tags: parse_tags(&tags_csv),
         folder_id: row.get("folder_id")?,
+        task_id: row.get("task_id")?,
+        color: row.get("color")?,
+        icon: row.get("icon")?,
+        sort_order: row.get("sort_order")?,
         created_at: row.get("created_at")?,
         updated_at: row.get("updated_at")?,
     })
@@ -261,6 +727,31 @@ fn map_note_attachment_row(row: &rusqlite::Row<'_>) -> Result<NoteAttachment, ru
         note_id: row.get("note_id")?,
         filename: row.get("filename")?,
         path_relative: row.get("path_relative")?,
+        thumb_path_relative: row.get("thumb_path_relative")?,
+        created_at: row.get("created_at")?,
+    })
+}
+
+fn map_timer_session_row(row: &rusqlite::Row<'_>) -> Result<TimerSession, rusqlite::Error> {
+    Ok(TimerSession {
+        id: row.get("id")?,
+        task_id: row.get("task_id")?,
+        started_at: row.get("started_at")?,
+        planned_seconds: row.get("planned_seconds")?,
+        ended_at: row.get("ended_at")?,
+        completed: row.get::<_, i32>("completed")? == 1,
+    })
+}
+
+fn map_checkin_attachment_row(
+    row: &rusqlite::Row<'_>,
+) -> Result<CheckinAttachment, rusqlite::Error> {
+    Ok(CheckinAttachment {
+        id: row.get("id")?,
+        checkin_id: row.get("checkin_id")?,
+        filename: row.get("filename")?,
+        path_relative: row.get("path_relative")?,
+        thumb_path_relative: row.get("thumb_path_relative")?,
         created_at: row.get("created_at")?,
     })
 }
@@ -274,11 +765,23 @@ fn map_note_folder_row(row: &rusqlite::Row<'_>) -> Result<NoteFolder, rusqlite::
     })
 }
 
+fn map_project_row(row: &rusqlite::Row<'_>) -> Result<Project, rusqlite::Error> {
+    Ok(Project {
+        id: row.get("id")?,
+        name: row.get("name")?,
+        color: row.get("color")?,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
 fn map_checkin_person_row(row: &rusqlite::Row<'_>) -> Result<CheckinPerson, rusqlite::Error> {
     Ok(CheckinPerson {
         id: row.get("id")?,
         name: row.get("name")?,
         relationship: row.get("relationship")?,
+        cadence_interval: row.get("cadence_interval")?,
+        cadence_unit: row.get("cadence_unit")?,
         created_at: row.get("created_at")?,
         updated_at: row.get("updated_at")?,
     })
@@ -321,6 +824,38 @@ fn list_by_query(conn: &Connection, sql: &str, value: &str) -> Result<Vec<Task>,
     Ok(tasks)
 }
 
+/// Id of the tag named `name` (case-insensitively), created if it does not exist yet.
+fn ensure_tag(conn: &Connection, name: &str, now: &str) -> Result<String, String> {
+    let existing_tag_id: Option<String> = conn
+        .query_row(
+            "SELECT id FROM tags WHERE LOWER(name) = LOWER(?1) LIMIT 1",
+            params![name],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+    if let Some(id) = existing_tag_id {
+        return Ok(id);
+    }
+    let id = Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO tags (id, name, created_at) VALUES (?1, ?2, ?3)",
+        params![id, name, now],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(id)
+}
+
+/// Notes keep their tags in the CSV column only, but register them in `tags` so task and
+/// note tags share one vocabulary for `list_tags` and suggestions.
+fn register_note_tags(conn: &Connection, tags: &[String]) -> Result<(), String> {
+    let now = Utc::now().to_rfc3339();
+    for tag in normalize_task_tags(tags) {
+        ensure_tag(conn, &tag, &now)?;
+    }
+    Ok(())
+}
+
 fn sync_task_tags(conn: &Connection, task_id: &str, tags: &[String]) -> Result<(), String> {
     conn.execute("DELETE FROM task_tags WHERE task_id = ?1", params![task_id])
         .map_err(|err| err.to_string())?;
@@ -331,27 +866,7 @@ fn sync_task_tags(conn: &Connection, task_id: &str, tags: &[String]) -> Result<(
 
     let now = Utc::now().to_rfc3339();
     for tag in tags {
-        let existing_tag_id: Option<String> = conn
-            .query_row(
-                "SELECT id FROM tags WHERE LOWER(name) = LOWER(?1) LIMIT 1",
-                params![tag],
-                |row| row.get(0),
-            )
-            .optional()
-            .map_err(|err| err.to_string())?;
-
-        let tag_id = if let Some(id) = existing_tag_id {
-            id
-        } else {
-            let id = Uuid::new_v4().to_string();
-            conn.execute(
-                "INSERT INTO tags (id, name, created_at) VALUES (?1, ?2, ?3)",
-                params![id, tag, now],
-            )
-            .map_err(|err| err.to_string())?;
-            id
-        };
-
+        let tag_id = ensure_tag(conn, tag, &now)?;
         conn.execute(
             "INSERT OR IGNORE INTO task_tags (task_id, tag_id) VALUES (?1, ?2)",
             params![task_id, tag_id],
@@ -393,7 +908,10 @@ fn normalize_input(
 
     let timer_enabled = if input.timer_enabled { 1 } else { 0 };
     let timer_minutes = if input.timer_enabled {
-        Some(input.timer_minutes.unwrap_or(25).max(1))
+        let from_seconds = input
+            .timer_seconds
+            .map(|seconds| ((seconds.max(1) + 59) / 60) as i32);
+        Some(input.timer_minutes.or(from_seconds).unwrap_or(25).max(1))
     } else {
         None
     };
@@ -414,32 +932,187 @@ fn normalize_input(
     )
 }
 
-fn parse_weekdays_csv(value: Option<&str>) -> Vec<Weekday> {
-    let mut out = Vec::new();
-    if let Some(csv) = value {
-        for part in csv.split(',') {
-            let item = part.trim();
-            let day = match item {
-                "Mon" => Some(Weekday::Mon),
-                "Tue" => Some(Weekday::Tue),
-                "Wed" => Some(Weekday::Wed),
-                "Thu" => Some(Weekday::Thu),
-                "Fri" => Some(Weekday::Fri),
-                "Sat" => Some(Weekday::Sat),
-                "Sun" => Some(Weekday::Sun),
-                _ => None,
-            };
-            if let Some(day) = day {
-                out.push(day);
-            }
+/// Stored timer length in seconds: the exact value when given, else the minutes.
+fn normalize_timer_seconds(input: &TaskInput, timer_minutes: Option<i32>) -> Option<i64> {
+    if !input.timer_enabled {
+        return None;
+    }
+    let seconds = input
+        .timer_seconds
+        .unwrap_or(timer_minutes.unwrap_or(25) as i64 * 60);
+    Some(seconds.max(1))
+}
+
+fn normalize_target_date(value: Option<&str>) -> String {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .unwrap_or(INBOX_DATE)
+        .to_string()
+}
+
+/// Blank values mean "default"; colors must be `#rrggbb` and icons at most 8 characters.
+fn normalize_note_style(
+    color: Option<&str>,
+    icon: Option<&str>,
+) -> Result<(Option<String>, Option<String>), String> {
+    let color = color.map(str::trim).filter(|value| !value.is_empty());
+    if let Some(value) = color {
+        let valid = value.len() == 7
+            && value.starts_with('#')
+            && value[1..].chars().all(|ch| ch.is_ascii_hexdigit());
+        if !valid {
+            return Err(format!("Invalid color: {}", value));
         }
     }
-    out
+    let icon = icon.map(str::trim).filter(|value| !value.is_empty());
+    if icon.is_some_and(|value| value.chars().count() > 8) {
+        return Err("Icon must be at most 8 characters".to_string());
+    }
+    Ok((
+        color.map(|value| value.to_lowercase()),
+        icon.map(str::to_string),
+    ))
 }
 
-fn add_months_keep_day(base: NaiveDate, interval: i32) -> NaiveDate {
-    let mut year = base.year();
-    let mut month = base.month() as i32 + interval;
+fn normalize_catchup_policy(input: &TaskInput) -> Result<&'static str, String> {
+    match input.catchup_policy.as_deref().map(str::trim) {
+        _ if !input.is_recurring => Ok("skip"),
+        None | Some("") | Some("skip") => Ok("skip"),
+        Some("all") => Ok("all"),
+        Some(other) => Err(format!("Invalid catch-up policy: {}", other)),
+    }
+}
+
+/// Problems with a task's recurrence settings, judged by the same normalization
+/// `create_task` applies. Non-recurring input has none.
+pub fn validate_recurrence(input: &TaskInput) -> Vec<RecurrenceIssue> {
+    let mut issues = Vec::new();
+    if !input.is_recurring {
+        return issues;
+    }
+    let mut push = |level: &str, field: &str, message: String| {
+        issues.push(RecurrenceIssue {
+            level: level.to_string(),
+            field: field.to_string(),
+            message,
+        })
+    };
+    let (_, recurrence_type, interval, weekdays, _, _, _) = normalize_input(input);
+
+    match recurrence_type.as_deref().map(str::trim) {
+        None | Some("") => push(
+            "error",
+            "recurrence_type",
+            "Choose daily, weekly or monthly; without a type the task never repeats".to_string(),
+        ),
+        Some("daily") | Some("weekly") | Some("monthly") => {}
+        Some(other) => push(
+            "error",
+            "recurrence_type",
+            format!(
+                "Unknown recurrence type \"{}\"; the task will never repeat",
+                other
+            ),
+        ),
+    }
+    if input.recurrence_interval.is_some_and(|value| value < 1) {
+        push(
+            "warning",
+            "recurrence_interval",
+            format!(
+                "Interval must be at least 1; it will be saved as {}",
+                interval
+            ),
+        );
+    }
+    if recurrence_type.as_deref() == Some("weekly") {
+        let csv = weekdays.as_deref().unwrap_or("");
+        for item in csv
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+        {
+            if parse_weekdays_csv(Some(item)).is_empty() {
+                push(
+                    "warning",
+                    "recurrence_weekdays",
+                    format!("Unknown weekday \"{}\" will be ignored", item),
+                );
+            }
+        }
+        if parse_weekdays_csv(weekdays.as_deref()).is_empty() {
+            let every = if interval == 1 {
+                "every week".to_string()
+            } else {
+                format!("every {} weeks", interval)
+            };
+            push(
+                "warning",
+                "recurrence_weekdays",
+                format!(
+                    "Weekly rule has no weekdays; it will repeat on the same weekday {}",
+                    every
+                ),
+            );
+        }
+    } else if input
+        .recurrence_weekdays
+        .as_deref()
+        .is_some_and(|value| !value.trim().is_empty())
+    {
+        push(
+            "warning",
+            "recurrence_weekdays",
+            "Weekdays only apply to weekly rules and will be ignored".to_string(),
+        );
+    }
+    if recurrence_type.as_deref() == Some("monthly") {
+        let day = input
+            .target_date
+            .as_deref()
+            .and_then(|value| parse_date(value).ok())
+            .map(|date| date.day());
+        if let Some(day) = day.filter(|day| *day > 28) {
+            push(
+                "warning",
+                "target_date",
+                format!("Months without a day {} fall back to their last day", day),
+            );
+        }
+    }
+    if let Err(err) = normalize_catchup_policy(input) {
+        push("error", "catchup_policy", err);
+    }
+    issues
+}
+
+fn parse_weekdays_csv(value: Option<&str>) -> Vec<Weekday> {
+    let mut out = Vec::new();
+    if let Some(csv) = value {
+        for part in csv.split(',') {
+            let item = part.trim();
+            let day = match item {
+                "Mon" => Some(Weekday::Mon),
+                "Tue" => Some(Weekday::Tue),
+                "Wed" => Some(Weekday::Wed),
+                "Thu" => Some(Weekday::Thu),
+                "Fri" => Some(Weekday::Fri),
+                "Sat" => Some(Weekday::Sat),
+                "Sun" => Some(Weekday::Sun),
+                _ => None,
+            };
+            if let Some(day) = day {
+                out.push(day);
+            }
+        }
+    }
+    out
+}
+
+fn add_months_keep_day(base: NaiveDate, interval: i32) -> NaiveDate {
+    let mut year = base.year();
+    let mut month = base.month() as i32 + interval;
     while month > 12 {
         month -= 12;
         year += 1;
@@ -492,20 +1165,95 @@ fn next_occurrence_date(task: &Task, from: NaiveDate) -> NaiveDate {
     }
 }
 
+/// Titles of the recurring tasks that would land on each day of the week starting at
+/// `week_start`, projected from the latest occurrence of every unpaused series. Nothing
+/// is generated; every one of the seven dates is present in the result.
+pub fn recurring_week_preview(
+    conn: &Connection,
+    week_start: &str,
+) -> Result<BTreeMap<String, Vec<String>>, String> {
+    let start = parse_date(week_start)?;
+    let end = start + Duration::days(6);
+    let mut preview: BTreeMap<String, Vec<String>> = (0..7)
+        .map(|offset| {
+            let date = start + Duration::days(offset);
+            (date.format("%Y-%m-%d").to_string(), Vec::new())
+        })
+        .collect();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT * FROM tasks WHERE is_recurring = 1 AND recurrence_paused = 0 AND archived = 0
+       ORDER BY target_date ASC, created_at ASC",
+        )
+        .map_err(|err| err.to_string())?;
+    let tasks: Vec<Task> = stmt
+        .query_map([], map_task_row)
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+
+    // Project each series from its latest row on or before the week start (or its first
+    // row when it begins later), so days already completed this week still show up.
+    let mut anchors = HashMap::new();
+    for task in tasks {
+        let series = (
+            task.title.clone(),
+            task.recurrence_type.clone(),
+            task.recurrence_interval,
+            task.recurrence_weekdays.clone(),
+        );
+        match anchors.get(&series) {
+            Some(_) if task.target_date.as_str() > week_start => {}
+            _ => {
+                anchors.insert(series, task);
+            }
+        }
+    }
+    for task in anchors.into_values() {
+        let mut date = match parse_date(&task.target_date) {
+            Ok(date) => date,
+            Err(_) => continue,
+        };
+        while date <= end {
+            if date >= start {
+                if let Some(titles) = preview.get_mut(&date.format("%Y-%m-%d").to_string()) {
+                    titles.push(task.title.clone());
+                }
+            }
+            let next = next_occurrence_date(&task, date);
+            if next <= date {
+                break;
+            }
+            date = next;
+        }
+    }
+    for titles in preview.values_mut() {
+        titles.sort_by_key(|title| title.to_lowercase());
+    }
+    Ok(preview)
+}
+
+/// Rows that belong to the same recurring series: same title and rule. Callers bind the
+/// task's title, recurrence type, interval and weekdays to ?1..?4, in that order.
+const SAME_SERIES: &str = "title = ?1 AND is_recurring = 1
+       AND COALESCE(recurrence_type, '') = COALESCE(?2, '')
+       AND COALESCE(recurrence_interval, 1) = COALESCE(?3, 1)
+       AND COALESCE(recurrence_weekdays, '') = COALESCE(?4, '')";
+
 fn has_recurring_occurrence(conn: &Connection, task: &Task, date: &str) -> Result<bool, String> {
     let existing: Option<String> = conn
         .query_row(
-            "SELECT id FROM tasks WHERE title = ?1 AND target_date = ?2 AND is_recurring = 1
-       AND COALESCE(recurrence_type, '') = COALESCE(?3, '')
-       AND COALESCE(recurrence_interval, 1) = COALESCE(?4, 1)
-       AND COALESCE(recurrence_weekdays, '') = COALESCE(?5, '')
-       LIMIT 1",
+            &format!(
+                "SELECT id FROM tasks WHERE {} AND target_date = ?5 LIMIT 1",
+                SAME_SERIES
+            ),
             params![
                 task.title,
-                date,
                 task.recurrence_type,
                 task.recurrence_interval,
-                task.recurrence_weekdays
+                task.recurrence_weekdays,
+                date
             ],
             |row| row.get(0),
         )
@@ -514,7 +1262,30 @@ fn has_recurring_occurrence(conn: &Connection, task: &Task, date: &str) -> Resul
     Ok(existing.is_some())
 }
 
-fn insert_next_occurrence(conn: &Connection, source: &Task, next_date: &str) -> Result<(), String> {
+fn has_later_occurrence(conn: &Connection, task: &Task) -> Result<bool, String> {
+    conn.query_row(
+        &format!(
+            "SELECT EXISTS(SELECT 1 FROM tasks WHERE {} AND id != ?5 AND target_date > ?6)",
+            SAME_SERIES
+        ),
+        params![
+            task.title,
+            task.recurrence_type,
+            task.recurrence_interval,
+            task.recurrence_weekdays,
+            task.id,
+            task.target_date
+        ],
+        |row| row.get(0),
+    )
+    .map_err(|err| err.to_string())
+}
+
+fn insert_next_occurrence(
+    conn: &Connection,
+    source: &Task,
+    next_date: &str,
+) -> Result<String, String> {
     let id = Uuid::new_v4().to_string();
     let now = Utc::now().to_rfc3339();
     let sort_order = next_sort_order(conn, next_date, 0)?;
@@ -525,18 +1296,37 @@ fn insert_next_occurrence(conn: &Connection, source: &Task, next_date: &str) ->
     } else {
         None
     };
+    let notes = if source.recurrence_reset_notes {
+        None
+    } else {
+        source.notes.clone()
+    };
+    // Keep the deadline as far from the occurrence date as it was on the source task.
+    let deadline_at = match (
+        source.deadline_at.as_deref(),
+        parse_date(&source.target_date),
+    ) {
+        (Some(deadline), Ok(source_date)) => Some(shift_deadline(
+            deadline,
+            (parse_date(next_date)? - source_date).num_days(),
+        )?),
+        (deadline, _) => deadline.map(str::to_string),
+    };
     conn
     .execute(
-      "INSERT INTO tasks (id, title, notes, target_date, status, progress_percent, deadline_at, is_recurring,
-       recurrence_type, recurrence_interval, recurrence_weekdays, timer_enabled, timer_minutes,
-       timer_state, timer_ends_at, rolled_over, rolled_from_date, tags, sort_order, created_at, updated_at)
-       VALUES (?1, ?2, ?3, ?4, 'todo', 0, ?5, 1, ?6, ?7, ?8, ?9, ?10, ?11, NULL, 0, NULL, ?12, ?13, ?14, ?15)",
+      "INSERT INTO tasks (id, title, notes, target_date, status, progress_percent, deadline_at, deadline_all_day,
+       is_recurring, recurrence_type, recurrence_interval, recurrence_weekdays, timer_enabled, timer_minutes,
+       timer_state, timer_ends_at, rolled_over, rolled_from_date, tags, sort_order, created_at, updated_at,
+       catchup_policy, recurrence_reset_notes, recurrence_keep_missed, timer_seconds, project_id)
+       VALUES (?1, ?2, ?3, ?4, 'todo', 0, ?5, ?6, 1, ?7, ?8, ?9, ?10, ?11, ?12, NULL, 0, NULL, ?13, ?14, ?15, ?16,
+       ?17, ?18, ?19, ?20, ?21)",
       params![
         id,
         source.title,
-        source.notes,
+        notes,
         next_date,
-        source.deadline_at,
+        deadline_at,
+        if source.deadline_all_day { 1 } else { 0 },
         source.recurrence_type,
         source.recurrence_interval.unwrap_or(1),
         source.recurrence_weekdays,
@@ -546,39 +1336,91 @@ fn insert_next_occurrence(conn: &Connection, source: &Task, next_date: &str) ->
         tags_csv,
         sort_order,
         now,
-        now
+        now,
+        source.catchup_policy,
+        source.recurrence_reset_notes,
+        source.recurrence_keep_missed,
+        source.timer_seconds,
+        source.project_id
       ],
     )
     .map_err(|err| err.to_string())?;
     sync_task_tags(conn, &id, &normalized_tags)?;
-    Ok(())
+    Ok(id)
 }
 
-pub fn list_today(conn: &Connection) -> Result<Vec<Task>, String> {
-    let today_value = today();
-    list_by_query(
+/// Unarchived tasks planned between `start` and `end` (inclusive), in day order.
+pub fn tasks_in_range(conn: &Connection, start: &str, end: &str) -> Result<Vec<Task>, String> {
+    if parse_date(start)? > parse_date(end)? {
+        return Err("Start date must not be after end date".to_string());
+    }
+    let mut stmt = conn
+        .prepare(
+            "SELECT * FROM tasks WHERE target_date >= ?1 AND target_date <= ?2 AND archived = 0
+       ORDER BY target_date ASC, sort_order ASC, created_at ASC",
+        )
+        .map_err(|err| err.to_string())?;
+    let tasks = stmt
+        .query_map(params![start, end], map_task_row)
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    Ok(tasks)
+}
+
+/// Tasks from today through today + `days`, one entry per day (empty days included).
+pub fn agenda(conn: &Connection, today_value: &str, days: i64) -> Result<Vec<AgendaDay>, String> {
+    if !(1..=90).contains(&days) {
+        return Err("Days must be between 1 and 90".to_string());
+    }
+    let start = parse_date(today_value)?;
+    let end = start + Duration::days(days);
+    let tasks = tasks_in_range(
         conn,
-        "SELECT * FROM tasks WHERE target_date = ?1 ORDER BY sort_order ASC, created_at ASC",
-        &today_value,
-    )
+        &start.format("%Y-%m-%d").to_string(),
+        &end.format("%Y-%m-%d").to_string(),
+    )?;
+
+    let mut agenda: Vec<AgendaDay> = (0..=days)
+        .map(|offset| AgendaDay {
+            date: (start + Duration::days(offset))
+                .format("%Y-%m-%d")
+                .to_string(),
+            tasks: Vec::new(),
+        })
+        .collect();
+    for task in tasks {
+        if let Some(day) = agenda.iter_mut().find(|day| day.date == task.target_date) {
+            day.tasks.push(task);
+        }
+    }
+    Ok(agenda)
 }
 
-pub fn list_overview(conn: &Connection) -> Result<TaskOverview, String> {
-    let today_value = today();
-    let today_tasks = list_by_query(
-    conn,
-    "SELECT * FROM tasks WHERE target_date = ?1 AND rolled_over = 0 ORDER BY sort_order ASC, created_at ASC",
-    &today_value,
-  )?;
-    let rolled_over = list_by_query(
-    conn,
-    "SELECT * FROM tasks WHERE target_date = ?1 AND rolled_over = 1 ORDER BY sort_order ASC, created_at ASC",
-    &today_value,
-  )?;
+/// Tasks snoozed past `now` are left out of today's lists until the snooze ends. Missed days
+/// of a recurring task that keeps them are history, not overdue work, once a later
+/// occurrence exists.
+pub fn list_overview(
+    conn: &Connection,
+    today_value: &str,
+    now: DateTime<Utc>,
+) -> Result<TaskOverview, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT * FROM tasks WHERE target_date = ?1 AND archived = 0
+             AND (snooze_until IS NULL OR snooze_until <= ?2)
+             ORDER BY sort_order ASC, created_at ASC",
+        )
+        .map_err(|err| err.to_string())?;
+    let (rolled_over, today_tasks): (Vec<Task>, Vec<Task>) = stmt
+        .query_map(params![today_value, snooze_stamp(now)], map_task_row)
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .partition(|task| task.rolled_over);
 
     let mut stmt = conn
     .prepare(
-      "SELECT * FROM tasks WHERE target_date > ?1 ORDER BY target_date ASC, sort_order ASC, created_at ASC",
+      "SELECT * FROM tasks WHERE target_date > ?1 AND archived = 0 ORDER BY target_date ASC, sort_order ASC, created_at ASC",
     )
     .map_err(|err| err.to_string())?;
     let upcoming = stmt
@@ -587,475 +1429,2748 @@ pub fn list_overview(conn: &Connection) -> Result<TaskOverview, String> {
         .filter_map(Result::ok)
         .collect();
 
+    let mut overdue = Vec::new();
+    for task in list_by_query(
+        conn,
+        "SELECT * FROM tasks WHERE target_date < ?1 AND target_date != '' AND status != 'done'
+         AND archived = 0 ORDER BY target_date ASC, sort_order ASC, created_at ASC",
+        today_value,
+    )? {
+        let kept_missed_day = task.is_recurring
+            && task.recurrence_keep_missed
+            && !task.recurrence_paused
+            && has_later_occurrence(conn, &task)?;
+        if !kept_missed_day {
+            overdue.push(task);
+        }
+    }
+
     Ok(TaskOverview {
         today: today_tasks,
         rolled_over,
         upcoming,
+        overdue,
     })
 }
 
-pub fn list_tags(conn: &Connection) -> Result<Vec<String>, String> {
+/// Open tasks that have a deadline, soonest cutoff first. Deadlines that no longer parse
+/// are skipped rather than failing the whole list.
+pub fn tasks_by_deadline(
+    conn: &Connection,
+    now: NaiveDateTime,
+) -> Result<Vec<DeadlineTask>, String> {
     let mut stmt = conn
-        .prepare("SELECT name FROM tags ORDER BY name")
+        .prepare(
+            "SELECT * FROM tasks WHERE deadline_at IS NOT NULL AND status != 'done' AND archived = 0",
+        )
         .map_err(|err| err.to_string())?;
-    let tags = stmt
-        .query_map([], |row| row.get(0))
+    let tasks: Vec<Task> = stmt
+        .query_map([], map_task_row)
         .map_err(|err| err.to_string())?
         .filter_map(Result::ok)
         .collect();
-    Ok(tags)
+
+    let today = now.date();
+    let mut entries: Vec<(NaiveDateTime, Task)> = tasks
+        .into_iter()
+        .filter_map(|task| {
+            let cutoff =
+                deadline_cutoff(task.deadline_at.as_deref()?, task.deadline_all_day).ok()?;
+            Some((cutoff, task))
+        })
+        .collect();
+    entries.sort_by_key(|(cutoff, _)| *cutoff);
+    Ok(entries
+        .into_iter()
+        .map(|(cutoff, task)| {
+            let urgency = if cutoff < now {
+                "overdue"
+            } else if cutoff.date() == today {
+                "today"
+            } else if cutoff.date() < today + Duration::days(7) {
+                "this_week"
+            } else {
+                "later"
+            };
+            DeadlineTask {
+                task,
+                cutoff: cutoff.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                urgency: urgency.to_string(),
+            }
+        })
+        .collect())
 }
 
-pub fn create_task(conn: &Connection, input: TaskInput) -> Result<Task, String> {
-    let id = Uuid::new_v4().to_string();
-    let now = Utc::now().to_rfc3339();
-    let sort_order = next_sort_order(conn, &input.target_date, 0)?;
-    let normalized_tags = normalize_task_tags(&input.tags);
-    let tags_csv = normalize_tags(&normalized_tags);
-    let (
-        is_recurring,
-        recurrence_type,
-        recurrence_interval,
-        recurrence_weekdays,
-        timer_enabled,
-        timer_minutes,
-        timer_state,
-    ) = normalize_input(&input);
+/// Tasks that have rolled over the most times, done ones included so the report still
+/// shows what was eventually finished.
+pub fn most_deferred_tasks(conn: &Connection, limit: i64) -> Result<Vec<Task>, String> {
+    if !(1..=100).contains(&limit) {
+        return Err("Limit must be between 1 and 100".to_string());
+    }
+    let mut stmt = conn
+        .prepare(
+            "SELECT * FROM tasks WHERE rollover_count > 0 AND archived = 0
+       ORDER BY rollover_count DESC, target_date ASC, created_at ASC
+       LIMIT ?1",
+        )
+        .map_err(|err| err.to_string())?;
+    let tasks = stmt
+        .query_map(params![limit], map_task_row)
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    Ok(tasks)
+}
 
-    conn
-    .execute(
-      "INSERT INTO tasks (id, title, notes, target_date, status, progress_percent, deadline_at, is_recurring,
-       recurrence_type, recurrence_interval, recurrence_weekdays, timer_enabled, timer_minutes,
-       timer_state, timer_ends_at, rolled_over, rolled_from_date, tags, sort_order, created_at, updated_at)
-       VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, NULL, 0, NULL, ?15, ?16, ?17, ?18)",
-      params![
-        id,
-        input.title,
-        input.notes,
-        input.target_date,
-        input.status,
-        input.progress_percent.clamp(0, 100),
-        input.deadline_at,
-        is_recurring,
-        recurrence_type,
-        recurrence_interval,
-        recurrence_weekdays,
-        timer_enabled,
-        timer_minutes,
-        timer_state,
-        tags_csv,
-        sort_order,
-        now,
-        now
-      ],
-    )
-    .map_err(|err| err.to_string())?;
-    sync_task_tags(conn, &id, &normalized_tags)?;
-    get_task(conn, &id)
+pub fn overdue_report(conn: &Connection, today_value: &str) -> Result<Vec<OverdueTask>, String> {
+    let today_date = parse_date(today_value)?;
+    let tasks = list_by_query(
+        conn,
+        "SELECT * FROM tasks WHERE rolled_over = 1 AND status != 'done' AND archived = 0
+       AND target_date <= ?1",
+        today_value,
+    )?;
+
+    let mut report = Vec::new();
+    for task in tasks {
+        // Tasks rolled over before rolled_from_date was tracked fall back to their creation day.
+        let carried_since = match task.rolled_from_date.as_deref() {
+            Some(value) if !value.trim().is_empty() => value.trim().to_string(),
+            _ => task.created_at.chars().take(10).collect(),
+        };
+        let age_days = parse_date(&carried_since)
+            .map(|since| (today_date - since).num_days().max(0))
+            .unwrap_or(0);
+        report.push(OverdueTask {
+            task,
+            carried_since,
+            age_days,
+        });
+    }
+    report.sort_by_key(|entry| std::cmp::Reverse(entry.age_days));
+    Ok(report)
 }
 
-pub fn update_task(conn: &Connection, id: &str, input: TaskInput) -> Result<Task, String> {
-    let now = Utc::now().to_rfc3339();
-    let existing = get_task(conn, id)?;
-    let sort_order = if existing.target_date == input.target_date && !existing.rolled_over {
-        existing.sort_order
-    } else {
-        next_sort_order(conn, &input.target_date, 0)?
+/// UTC instant of midnight at the start of `date` in the app timezone `tz` (system local
+/// time when `None`), formatted like stored timestamps.
+fn local_day_start(date: NaiveDate, tz: Option<Tz>) -> Result<String, String> {
+    let midnight = date.and_time(NaiveTime::MIN);
+    let at = match tz {
+        Some(tz) => tz
+            .from_local_datetime(&midnight)
+            .earliest()
+            .map(|at| at.with_timezone(&Utc)),
+        None => Local
+            .from_local_datetime(&midnight)
+            .earliest()
+            .map(|at| at.with_timezone(&Utc)),
     };
-    let normalized_tags = normalize_task_tags(&input.tags);
-    let tags_csv = normalize_tags(&normalized_tags);
-    let (
-        is_recurring,
-        recurrence_type,
-        recurrence_interval,
-        recurrence_weekdays,
-        timer_enabled,
-        timer_minutes,
-        timer_state,
-    ) = normalize_input(&input);
+    at.map(|at| at.to_rfc3339())
+        .ok_or_else(|| format!("Invalid local date: {}", date))
+}
 
-    conn
-    .execute(
-      "UPDATE tasks SET title = ?1, notes = ?2, target_date = ?3, status = ?4, progress_percent = ?5,
-       deadline_at = ?6, is_recurring = ?7, recurrence_type = ?8, recurrence_interval = ?9,
-       recurrence_weekdays = ?10, timer_enabled = ?11, timer_minutes = ?12, timer_state = ?13,
-       timer_ends_at = NULL, rolled_over = 0, rolled_from_date = NULL, tags = ?14, sort_order = ?15,
-       updated_at = ?16 WHERE id = ?17",
-      params![
-        input.title,
-        input.notes,
-        input.target_date,
-        input.status,
-        input.progress_percent.clamp(0, 100),
-        input.deadline_at,
-        is_recurring,
-        recurrence_type,
-        recurrence_interval,
-        recurrence_weekdays,
-        timer_enabled,
-        timer_minutes,
-        timer_state,
-        tags_csv,
-        sort_order,
-        now,
-        id
-      ],
-    )
-    .map_err(|err| err.to_string())?;
-    sync_task_tags(conn, id, &normalized_tags)?;
-    get_task(conn, id)
+/// Wall-clock time of a stored timestamp in the app timezone `tz` (system local when `None`).
+fn local_time_of(at: DateTime<FixedOffset>, tz: Option<Tz>) -> NaiveDateTime {
+    match tz {
+        Some(tz) => at.with_timezone(&tz).naive_local(),
+        None => at.with_timezone(&Local).naive_local(),
+    }
 }
 
-pub fn update_status(conn: &Connection, id: &str, status: &str) -> Result<Task, String> {
-    let now = Utc::now().to_rfc3339();
-    let task = get_task(conn, id)?;
-    if status == "done" {
-        let bucket = if task.rolled_over { 1 } else { 0 };
-        let new_sort_order = next_sort_order(conn, &task.target_date, bucket)?;
-        conn.execute(
-            "UPDATE tasks SET status = ?1, sort_order = ?2, updated_at = ?3 WHERE id = ?4",
-            params![status, new_sort_order, now, id],
+/// Tasks completed on local dates `start_date..=end_date`, ordered by completion time.
+pub fn completed_between(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+    tz: Option<Tz>,
+) -> Result<Vec<Task>, String> {
+    let start = parse_date(start_date.trim())?;
+    let end = parse_date(end_date.trim())?;
+    if end < start {
+        return Err("End date must not be before start date".to_string());
+    }
+    let mut stmt = conn
+        .prepare(
+            "SELECT * FROM tasks WHERE done_at >= ?1 AND done_at < ?2 AND status = 'done'
+       ORDER BY done_at ASC",
         )
         .map_err(|err| err.to_string())?;
-    } else {
-        conn.execute(
-            "UPDATE tasks SET status = ?1, updated_at = ?2 WHERE id = ?3",
-            params![status, now, id],
+    let tasks = stmt
+        .query_map(
+            params![
+                local_day_start(start, tz)?,
+                local_day_start(end + Duration::days(1), tz)?
+            ],
+            map_task_row,
         )
-        .map_err(|err| err.to_string())?;
-    }
-    get_task(conn, id)
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    Ok(tasks)
 }
 
-pub fn set_status(conn: &Connection, id: &str, status: &str) -> Result<Task, String> {
-    update_status(conn, id, status)
-}
+const COMPLETION_DEFAULT_DAYS: i64 = 30;
+const COMPLETION_MAX_DAYS: i64 = 366;
 
-pub fn mark_done_and_generate_next(conn: &Connection, id: &str) -> Result<Task, String> {
-    let task = update_status(conn, id, "done")?;
-    if task.is_recurring {
-        let base = parse_date(&task.target_date)?;
-        let next = next_occurrence_date(&task, base);
-        let next_str = next.format("%Y-%m-%d").to_string();
-        if !has_recurring_occurrence(conn, &task, &next_str)? {
-            insert_next_occurrence(conn, &task, &next_str)?;
+/// Tasks completed per hour of day (index 0..=23) in the app timezone `tz`, on dates
+/// `start_date..=end_date`.
+/// Without `start_date` the range covers the 30 days ending on `end_date`.
+pub fn completion_by_hour(
+    conn: &Connection,
+    start_date: Option<&str>,
+    end_date: &str,
+    tz: Option<Tz>,
+) -> Result<Vec<i64>, String> {
+    let end = parse_date(end_date.trim())?;
+    let start = match start_date.map(str::trim).filter(|value| !value.is_empty()) {
+        Some(value) => parse_date(value)?,
+        None => end - Duration::days(COMPLETION_DEFAULT_DAYS - 1),
+    };
+    if end < start {
+        return Err("End date must not be before start date".to_string());
+    }
+    if (end - start).num_days() >= COMPLETION_MAX_DAYS {
+        return Err(format!(
+            "Range must be at most {} days",
+            COMPLETION_MAX_DAYS
+        ));
+    }
+    let mut stmt = conn
+        .prepare("SELECT done_at FROM tasks WHERE done_at >= ?1 AND done_at < ?2")
+        .map_err(|err| err.to_string())?;
+    let stamps: Vec<String> = stmt
+        .query_map(
+            params![
+                local_day_start(start, tz)?,
+                local_day_start(end + Duration::days(1), tz)?
+            ],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    let mut counts = vec![0; 24];
+    for stamp in stamps {
+        if let Ok(at) = DateTime::parse_from_rfc3339(&stamp) {
+            counts[local_time_of(at, tz).hour() as usize] += 1;
         }
     }
-    get_task(conn, id)
+    Ok(counts)
 }
 
-pub fn get_task(conn: &Connection, id: &str) -> Result<Task, String> {
+/// Most recently updated tasks, notes and check-ins, newest first. Check-ins are titled
+/// by the person they were held with.
+pub fn recent_activity(conn: &Connection, limit: i64) -> Result<Vec<ActivityItem>, String> {
+    if !(1..=200).contains(&limit) {
+        return Err("Limit must be between 1 and 200".to_string());
+    }
     let mut stmt = conn
-        .prepare("SELECT * FROM tasks WHERE id = ?1")
-        .map_err(|err| err.to_string())?;
-    let task = stmt
-        .query_row(params![id], map_task_row)
+        .prepare(
+            "SELECT 'task' AS kind, id, title, updated_at FROM tasks
+       UNION ALL
+       SELECT 'note', id, title, updated_at FROM notes
+       UNION ALL
+       SELECT 'checkin', checkins.id, checkin_people.name, checkins.updated_at
+       FROM checkins JOIN checkin_people ON checkin_people.id = checkins.person_id
+       ORDER BY updated_at DESC
+       LIMIT ?1",
+        )
         .map_err(|err| err.to_string())?;
-    Ok(task)
-}
-
-pub fn start_timer(conn: &Connection, id: &str, ends_at: &str) -> Result<(), String> {
-    conn
-    .execute(
-      "UPDATE tasks SET timer_state = 'running', timer_ends_at = ?1, updated_at = ?2 WHERE id = ?3",
-      params![ends_at, Utc::now().to_rfc3339(), id],
-    )
-    .map_err(|err| err.to_string())?;
-    Ok(())
-}
-
-pub fn finish_timer(conn: &Connection, id: &str) -> Result<(), String> {
-    conn
-    .execute(
-      "UPDATE tasks SET timer_state = 'finished', timer_ends_at = NULL, updated_at = ?1 WHERE id = ?2",
-      params![Utc::now().to_rfc3339(), id],
-    )
-    .map_err(|err| err.to_string())?;
-    Ok(())
+    let items = stmt
+        .query_map(params![limit], |row| {
+            Ok(ActivityItem {
+                kind: row.get(0)?,
+                id: row.get(1)?,
+                title: row.get(2)?,
+                updated_at: row.get(3)?,
+            })
+        })
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    Ok(items)
 }
 
-pub fn stop_timer(conn: &Connection, id: &str) -> Result<(), String> {
-    conn
-    .execute(
-      "UPDATE tasks SET timer_state = 'paused', timer_ends_at = NULL, updated_at = ?1 WHERE id = ?2",
-      params![Utc::now().to_rfc3339(), id],
-    )
-    .map_err(|err| err.to_string())?;
-    Ok(())
-}
+/// Dates in `start_date..=end_date` with at least one task, check-in or created note,
+/// sorted ascending. Note dates are taken in the app timezone `tz`.
+pub fn active_dates(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+    tz: Option<Tz>,
+) -> Result<Vec<String>, String> {
+    let start = parse_date(start_date.trim())?;
+    let end = parse_date(end_date.trim())?;
+    if end < start {
+        return Err("End date must not be before start date".to_string());
+    }
+    let start_str = start.format("%Y-%m-%d").to_string();
+    let end_str = end.format("%Y-%m-%d").to_string();
 
-pub fn delete_task(conn: &Connection, id: &str) -> Result<(), String> {
-    conn.execute("DELETE FROM tasks WHERE id = ?1", params![id])
+    let mut dates = BTreeSet::new();
+    let mut stmt = conn
+        .prepare(
+            "SELECT target_date FROM tasks WHERE target_date BETWEEN ?1 AND ?2
+       UNION
+       SELECT checkin_date FROM checkins WHERE checkin_date BETWEEN ?1 AND ?2",
+        )
         .map_err(|err| err.to_string())?;
-    Ok(())
-}
+    let rows = stmt
+        .query_map(params![start_str, end_str], |row| row.get::<_, String>(0))
+        .map_err(|err| err.to_string())?;
+    dates.extend(rows.filter_map(Result::ok));
 
-pub fn move_task(conn: &Connection, id: &str, direction: &str) -> Result<(), String> {
-    let (target_date, rolled_over, sort_order): (String, i32, i64) = conn
-        .query_row(
-            "SELECT target_date, rolled_over, sort_order FROM tasks WHERE id = ?1",
-            params![id],
-            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    let mut stmt = conn
+        .prepare("SELECT created_at FROM notes WHERE created_at >= ?1 AND created_at < ?2")
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(
+            params![
+                local_day_start(start, tz)?,
+                local_day_start(end + Duration::days(1), tz)?
+            ],
+            |row| row.get::<_, String>(0),
         )
         .map_err(|err| err.to_string())?;
+    for created_at in rows.filter_map(Result::ok) {
+        if let Ok(at) = DateTime::parse_from_rfc3339(&created_at) {
+            let date = local_time_of(at, tz).date();
+            dates.insert(date.format("%Y-%m-%d").to_string());
+        }
+    }
+    Ok(dates.into_iter().collect())
+}
 
-    let (comparison, sort_direction) = match direction {
-        "up" => ("<", "DESC"),
-        "down" => (">", "ASC"),
-        _ => return Err("Invalid move direction".to_string()),
-    };
-
-    let sql = format!(
-        "SELECT id, sort_order FROM tasks
-     WHERE target_date = ?1 AND rolled_over = ?2 AND id != ?3 AND sort_order {} ?4
-     ORDER BY sort_order {} LIMIT 1",
-        comparison, sort_direction
-    );
+pub fn weekly_review(
+    conn: &Connection,
+    week_start: &str,
+    tz: Option<Tz>,
+) -> Result<WeeklyReview, String> {
+    let start = parse_date(week_start.trim())?;
+    let end = start + Duration::days(6);
+    let start_value = start.format("%Y-%m-%d").to_string();
+    let end_value = end.format("%Y-%m-%d").to_string();
+    let window_start = local_day_start(start, tz)?;
+    let window_end = local_day_start(end + Duration::days(1), tz)?;
 
-    let neighbor: Option<(String, i64)> = conn
+    let completed = completed_between(conn, &start_value, &end_value, tz)?;
+    let created_count: i64 = conn
         .query_row(
-            &sql,
-            params![target_date, rolled_over, id, sort_order],
-            |row| Ok((row.get(0)?, row.get(1)?)),
+            "SELECT COUNT(*) FROM tasks WHERE created_at >= ?1 AND created_at < ?2",
+            params![window_start, window_end],
+            |row| row.get(0),
         )
-        .optional()
         .map_err(|err| err.to_string())?;
 
-    if let Some((neighbor_id, neighbor_sort_order)) = neighbor {
-        let now = Utc::now().to_rfc3339();
-        conn.execute(
-            "UPDATE tasks SET sort_order = ?1, updated_at = ?2 WHERE id = ?3",
-            params![neighbor_sort_order, now, id],
+    let mut stmt = conn
+        .prepare(
+            "SELECT * FROM tasks
+       WHERE rollover_count > 1 AND target_date >= ?1 AND target_date <= ?2 AND archived = 0
+       ORDER BY rollover_count DESC, target_date ASC",
         )
         .map_err(|err| err.to_string())?;
-        conn.execute(
-            "UPDATE tasks SET sort_order = ?1, updated_at = ?2 WHERE id = ?3",
-            params![sort_order, now, neighbor_id],
+    let repeatedly_rolled = stmt
+        .query_map(params![start_value, end_value], map_task_row)
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+
+    let checkins_held: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM checkins WHERE checkin_date >= ?1 AND checkin_date <= ?2",
+            params![start_value, end_value],
+            |row| row.get(0),
         )
         .map_err(|err| err.to_string())?;
-    }
-
-    Ok(())
-}
 
-pub fn reorder_tasks(conn: &Connection, task_ids: &[String]) -> Result<(), String> {
-    let now = Utc::now().to_rfc3339();
-    for (index, task_id) in task_ids.iter().enumerate() {
-        conn.execute(
-            "UPDATE tasks SET sort_order = ?1, updated_at = ?2 WHERE id = ?3",
-            params![(index as i64) + 1, now, task_id],
-        )
+    // Stopped sessions count only the time actually run, never more than planned.
+    let mut stmt = conn
+        .prepare("SELECT * FROM timer_sessions WHERE ended_at >= ?1 AND ended_at < ?2")
         .map_err(|err| err.to_string())?;
-    }
-    Ok(())
+    let focused_seconds: i64 = stmt
+        .query_map(params![window_start, window_end], map_timer_session_row)
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .map(|session| {
+            let ran = match (
+                DateTime::parse_from_rfc3339(&session.started_at),
+                session
+                    .ended_at
+                    .as_deref()
+                    .map(DateTime::parse_from_rfc3339),
+            ) {
+                (Ok(started), Some(Ok(ended))) => (ended - started).num_seconds().max(0),
+                _ => 0,
+            };
+            if session.completed {
+                session.planned_seconds
+            } else {
+                ran.min(session.planned_seconds)
+            }
+        })
+        .sum();
+
+    Ok(WeeklyReview {
+        week_start: start_value,
+        week_end: end_value,
+        completed,
+        created_count,
+        repeatedly_rolled,
+        checkins_held,
+        focused_seconds,
+    })
 }
 
-pub fn rollover_tasks(conn: &Connection) -> Result<usize, String> {
-    let today_value = today();
+pub fn status_counts(conn: &Connection, date: &str) -> Result<TaskStatusCounts, String> {
+    let date = date.trim().to_string();
+    let _ = parse_date(&date)?;
+
     let mut stmt = conn
-        .prepare("SELECT id, target_date FROM tasks WHERE target_date < ?1 AND status != 'done'")
+        .prepare(
+            "SELECT status, COUNT(*), SUM(rolled_over) FROM tasks
+       WHERE target_date = ?1 AND archived = 0
+       GROUP BY status",
+        )
         .map_err(|err| err.to_string())?;
-    let rows: Vec<(String, String)> = stmt
-        .query_map(params![today_value.clone()], |row| {
-            Ok((row.get(0)?, row.get(1)?))
+    let rows: Vec<(String, i64, i64)> = stmt
+        .query_map(params![date], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
         })
         .map_err(|err| err.to_string())?
         .filter_map(Result::ok)
         .collect();
 
-    let mut count = 0;
-    for (id, from_date) in rows {
-        let position = next_sort_order(conn, &today_value, 1)?;
-        conn
-      .execute(
-        "UPDATE tasks SET target_date = ?1, rolled_over = 1, rolled_from_date = ?2, sort_order = ?3, updated_at = ?4 WHERE id = ?5",
-        params![
-          today_value.clone(),
-          from_date,
-          position,
-          Utc::now().to_rfc3339(),
-          id
-        ],
-      )
-      .map_err(|err| err.to_string())?;
-        count += 1;
+    let mut counts = BTreeMap::new();
+    let mut rolled_over = 0;
+    for (status, count, rolled) in rows {
+        counts.insert(status, count);
+        rolled_over += rolled;
     }
-    Ok(count)
+    Ok(TaskStatusCounts {
+        date,
+        counts,
+        rolled_over,
+    })
 }
 
-pub fn ensure_recurrences(conn: &Connection) -> Result<(), String> {
-    let today_value = today();
-    let today_date = parse_date(&today_value)?;
-
+pub fn list_tags(conn: &Connection) -> Result<Vec<String>, String> {
     let mut stmt = conn
-        .prepare("SELECT * FROM tasks WHERE is_recurring = 1")
+        .prepare("SELECT name FROM tags ORDER BY name")
         .map_err(|err| err.to_string())?;
-
-    let recurring_tasks: Vec<Task> = stmt
-        .query_map([], map_task_row)
+    let tags = stmt
+        .query_map([], |row| row.get(0))
         .map_err(|err| err.to_string())?
         .filter_map(Result::ok)
         .collect();
+    Ok(tags)
+}
 
-    for task in recurring_tasks {
-        if task.recurrence_type.is_none() {
-            continue;
-        }
-
-        if task.status == "done" {
-            let base = parse_date(&task.target_date)?;
-            let next = next_occurrence_date(&task, base);
-            let next_str = next.format("%Y-%m-%d").to_string();
-            if !has_recurring_occurrence(conn, &task, &next_str)? {
-                insert_next_occurrence(conn, &task, &next_str)?;
-            }
-            continue;
-        }
-
-        let mut date = parse_date(&task.target_date)?;
-        if date >= today_date {
-            continue;
-        }
+/// Completion of every unarchived task carrying `tag` (matched case-insensitively).
+/// Done tasks count as 100% towards the average.
+pub fn tag_progress(conn: &Connection, tag: &str) -> Result<TagProgress, String> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Err("Tag is required".to_string());
+    }
+    conn.query_row(
+        "SELECT COUNT(*),
+         COALESCE(SUM(CASE WHEN t.status = 'done' THEN 1 ELSE 0 END), 0),
+         COALESCE(AVG(CASE WHEN t.status = 'done' THEN 100 ELSE t.progress_percent END), 0)
+       FROM tasks t
+       INNER JOIN task_tags tt ON tt.task_id = t.id
+       INNER JOIN tags g ON g.id = tt.tag_id
+       WHERE LOWER(g.name) = LOWER(?1) AND t.archived = 0",
+        params![tag],
+        |row| {
+            Ok(TagProgress {
+                tag: tag.to_string(),
+                total: row.get(0)?,
+                done: row.get(1)?,
+                average_progress: row.get(2)?,
+            })
+        },
+    )
+    .map_err(|err| err.to_string())
+}
 
-        while date < today_date {
-            date = next_occurrence_date(&task, date);
-        }
+fn rank_tags(mut tags: Vec<String>, query: &str, limit: usize) -> Vec<String> {
+    let query = query.trim().to_lowercase();
+    tags.sort_by_cached_key(|tag| {
+        let lower = tag.to_lowercase();
+        let group = if lower.starts_with(&query) {
+            0
+        } else if lower.contains(&query) {
+            1
+        } else {
+            2
+        };
+        (group, lower)
+    });
+    tags.truncate(limit);
+    tags
+}
 
-        let date_str = date.format("%Y-%m-%d").to_string();
-        conn.execute(
-            "UPDATE tasks SET target_date = ?1, sort_order = ?2, updated_at = ?3 WHERE id = ?4",
-            params![
-                date_str,
-                next_sort_order(conn, &date_str, if task.rolled_over { 1 } else { 0 })?,
-                Utc::now().to_rfc3339(),
-                task.id
-            ],
+pub fn tag_cooccurrence(conn: &Connection, min_count: i64) -> Result<Vec<TagPair>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT ta.name, tb.name, COUNT(*) AS shared
+       FROM task_tags a
+       INNER JOIN task_tags b ON b.task_id = a.task_id AND a.tag_id < b.tag_id
+       INNER JOIN tags ta ON ta.id = a.tag_id
+       INNER JOIN tags tb ON tb.id = b.tag_id
+       GROUP BY a.tag_id, b.tag_id
+       HAVING shared >= ?1
+       ORDER BY shared DESC, ta.name ASC, tb.name ASC",
         )
         .map_err(|err| err.to_string())?;
-    }
-
-    Ok(())
+    let pairs = stmt
+        .query_map(params![min_count.max(1)], |row| {
+            Ok(TagPair {
+                tag_a: row.get(0)?,
+                tag_b: row.get(1)?,
+                count: row.get(2)?,
+            })
+        })
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    Ok(pairs)
 }
 
-pub fn list_notes(conn: &Connection) -> Result<Vec<Note>, String> {
+/// Every tag used on notes with the number of notes carrying it, most used first. Tags
+/// are merged case-insensitively and keep the first spelling seen.
+pub fn note_tags_with_counts(conn: &Connection) -> Result<Vec<TagCount>, String> {
     let mut stmt = conn
-    .prepare("SELECT id, title, body_markdown, tags, folder_id, created_at, updated_at FROM notes ORDER BY updated_at DESC")
-    .map_err(|err| err.to_string())?;
-    let notes = stmt
-        .query_map([], map_note_row)
+        .prepare("SELECT tags FROM notes WHERE tags != '' ORDER BY created_at ASC")
+        .map_err(|err| err.to_string())?;
+    let rows: Vec<String> = stmt
+        .query_map([], |row| row.get(0))
         .map_err(|err| err.to_string())?
         .filter_map(Result::ok)
         .collect();
-    Ok(notes)
+
+    let mut counts: BTreeMap<String, TagCount> = BTreeMap::new();
+    for value in rows {
+        let mut seen = HashSet::new();
+        for tag in parse_tags(&value) {
+            let key = tag.to_lowercase();
+            if !seen.insert(key.clone()) {
+                continue;
+            }
+            counts
+                .entry(key)
+                .or_insert_with(|| TagCount { tag, count: 0 })
+                .count += 1;
+        }
+    }
+    let mut tags: Vec<TagCount> = counts.into_values().collect();
+    tags.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.tag.to_lowercase().cmp(&b.tag.to_lowercase()))
+    });
+    Ok(tags)
 }
 
-pub fn get_note(conn: &Connection, id: &str) -> Result<Note, String> {
-    let mut stmt = conn
-    .prepare("SELECT id, title, body_markdown, tags, folder_id, created_at, updated_at FROM notes WHERE id = ?1")
-    .map_err(|err| err.to_string())?;
-    stmt.query_row(params![id], map_note_row)
-        .map_err(|err| err.to_string())
+/// Prefix matches first, then substring matches, then everything else; alphabetical within
+/// each group and case-insensitive throughout.
+pub fn suggest_tags(conn: &Connection, query: &str, limit: usize) -> Result<Vec<String>, String> {
+    Ok(rank_tags(list_tags(conn)?, query, limit))
 }
 
-pub fn create_note(conn: &Connection, input: NoteInput) -> Result<Note, String> {
+pub fn create_task(conn: &Connection, input: TaskInput) -> Result<Task, String> {
     let id = Uuid::new_v4().to_string();
     let now = Utc::now().to_rfc3339();
-    let title = if input.title.trim().is_empty() {
-        "Untitled note".to_string()
-    } else {
-        input.title.trim().to_string()
-    };
-    let tags_csv = normalize_tags(&input.tags);
+    let target_date = normalize_target_date(input.target_date.as_deref());
+    let sort_order = next_sort_order(conn, &target_date, 0)?;
+    let normalized_tags = normalize_task_tags(&input.tags);
+    let tags_csv = normalize_tags(&normalized_tags);
+    let (
+        is_recurring,
+        recurrence_type,
+        recurrence_interval,
+        recurrence_weekdays,
+        timer_enabled,
+        timer_minutes,
+        timer_state,
+    ) = normalize_input(&input);
+    let timer_seconds = normalize_timer_seconds(&input, timer_minutes);
+    let (deadline_at, deadline_all_day) =
+        normalize_deadline(input.deadline_at.as_deref(), input.deadline_all_day)?;
+    let (reminder_at, reminder_state) =
+        normalize_task_reminder(input.reminder_at.as_deref(), &input.status, None)?;
+    let catchup_policy = normalize_catchup_policy(&input)?;
+    let project_id = normalize_project_id(conn, input.project_id.as_deref())?;
 
     conn
     .execute(
-      "INSERT INTO notes (id, title, body_markdown, tags, folder_id, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-      params![id, title, input.body_markdown, tags_csv, input.folder_id, now, now],
-    )
-    .map_err(|err| err.to_string())?;
-    get_note(conn, &id)
+      "INSERT INTO tasks (id, title, notes, target_date, status, progress_percent, deadline_at, deadline_all_day,
+       is_recurring, recurrence_type, recurrence_interval, recurrence_weekdays, timer_enabled, timer_minutes,
+       timer_state, timer_ends_at, rolled_over, rolled_from_date, tags, sort_order, reminder_at, reminder_state,
+       created_at, updated_at, catchup_policy, done_at, recurrence_paused, recurrence_reset_notes,
+       recurrence_keep_missed, timer_seconds, project_id)
+       VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, NULL, 0, NULL, ?16, ?17, ?18, ?19,
+       ?20, ?21, ?22, CASE WHEN ?5 = 'done' THEN ?20 END, ?23, ?24, ?25, ?26, ?27)",
+      params![
+        id,
+        input.title,
+        input.notes,
+        target_date,
+        input.status,
+        input.progress_percent.clamp(0, 100),
+        deadline_at,
+        deadline_all_day,
+        is_recurring,
+        recurrence_type,
+        recurrence_interval,
+        recurrence_weekdays,
+        timer_enabled,
+        timer_minutes,
+        timer_state,
+        tags_csv,
+        sort_order,
+        reminder_at,
+        reminder_state,
+        now,
+        now,
+        catchup_policy,
+        is_recurring == 1 && input.recurrence_paused.unwrap_or(false),
+        is_recurring == 1 && input.recurrence_reset_notes,
+        is_recurring == 1 && input.recurrence_keep_missed,
+        timer_seconds,
+        project_id
+      ],
+    )
+    .map_err(|err| err.to_string())?;
+    sync_task_tags(conn, &id, &normalized_tags)?;
+    get_task(conn, &id)
+}
+
+/// Creates a plain todo for `target_date` from just a title, for the global quick-add.
+pub fn quick_add_task(conn: &Connection, title: &str, target_date: &str) -> Result<Task, String> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err("Title is required".to_string());
+    }
+    create_task(conn, plain_task_input(title, target_date))
+}
+
+fn plain_task_input(title: &str, target_date: &str) -> TaskInput {
+    TaskInput {
+        title: title.to_string(),
+        notes: None,
+        tags: Vec::new(),
+        target_date: Some(target_date.to_string()),
+        status: "todo".to_string(),
+        progress_percent: 0,
+        deadline_at: None,
+        deadline_all_day: false,
+        is_recurring: false,
+        recurrence_type: None,
+        recurrence_interval: None,
+        recurrence_weekdays: None,
+        catchup_policy: None,
+        recurrence_paused: None,
+        recurrence_reset_notes: false,
+        recurrence_keep_missed: false,
+        timer_enabled: false,
+        timer_minutes: None,
+        timer_seconds: None,
+        reminder_at: None,
+        project_id: None,
+    }
+}
+
+/// Turns one check-in action item into a task on `target_date`, tagged with the person's
+/// name and linked back through `source_checkin_id`.
+pub fn promote_action_item_to_task(
+    conn: &Connection,
+    checkin_id: &str,
+    line_text: &str,
+    target_date: &str,
+) -> Result<Task, String> {
+    let checkin = get_checkin(conn, checkin_id)?;
+    let person = get_checkin_person(conn, &checkin.person_id)?;
+    let title = list_item_text(line_text);
+    if title.is_empty() {
+        return Err("Action item is empty".to_string());
+    }
+    parse_date(target_date)?;
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|err| err.to_string())?;
+    let mut input = plain_task_input(title, target_date);
+    input.tags = vec![person.name];
+    let task = create_task(&tx, input)?;
+    tx.execute(
+        "UPDATE tasks SET source_checkin_id = ?1 WHERE id = ?2",
+        params![checkin.id, task.id],
+    )
+    .map_err(|err| err.to_string())?;
+    let task = get_task(&tx, &task.id)?;
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(task)
+}
+
+/// Creates one task per checklist item of `markdown` on `target_date`, in document order.
+/// Checked items are created done; indented sub-items become the task notes.
+pub fn import_markdown_tasks(
+    conn: &Connection,
+    markdown: &str,
+    target_date: &str,
+) -> Result<Vec<Task>, String> {
+    parse_date(target_date)?;
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|err| err.to_string())?;
+    let mut created = Vec::new();
+    for item in parse_checklist(markdown) {
+        let mut input = plain_task_input(&item.title, target_date);
+        input.notes = item.notes;
+        if item.done {
+            input.status = "done".to_string();
+        }
+        created.push(create_task(&tx, input)?);
+    }
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(created)
+}
+
+fn ensure_row_updated(changed: usize, expected_updated_at: Option<&str>) -> Result<(), String> {
+    if changed == 0 && expected_updated_at.is_some() {
+        return Err(MODIFIED_ELSEWHERE.to_string());
+    }
+    Ok(())
+}
+
+/// `today_value` is the app's today, used to skip occurrences missed while paused when
+/// the edit resumes the series.
+pub fn update_task(
+    conn: &Connection,
+    id: &str,
+    input: TaskInput,
+    expected_updated_at: Option<&str>,
+    today_value: &str,
+) -> Result<Task, String> {
+    let now = Utc::now().to_rfc3339();
+    let existing = get_task(conn, id)?;
+    let target_date = normalize_target_date(input.target_date.as_deref());
+    let sort_order = if existing.target_date == target_date && !existing.rolled_over {
+        existing.sort_order
+    } else {
+        next_sort_order(conn, &target_date, 0)?
+    };
+    let normalized_tags = normalize_task_tags(&input.tags);
+    let tags_csv = normalize_tags(&normalized_tags);
+    let (
+        is_recurring,
+        recurrence_type,
+        recurrence_interval,
+        recurrence_weekdays,
+        timer_enabled,
+        timer_minutes,
+        timer_state,
+    ) = normalize_input(&input);
+    let timer_seconds = normalize_timer_seconds(&input, timer_minutes);
+    let (deadline_at, deadline_all_day) =
+        normalize_deadline(input.deadline_at.as_deref(), input.deadline_all_day)?;
+    let (reminder_at, reminder_state) =
+        normalize_task_reminder(input.reminder_at.as_deref(), &input.status, Some(&existing))?;
+    let catchup_policy = normalize_catchup_policy(&input)?;
+    let recurrence_paused = input
+        .recurrence_paused
+        .unwrap_or(existing.recurrence_paused);
+    let project_id = match input.project_id.as_deref() {
+        Some(project_id) => normalize_project_id(conn, Some(project_id))?,
+        None => existing.project_id.clone(),
+    };
+
+    let changed = conn
+    .execute(
+      "UPDATE tasks SET title = ?1, notes = ?2, target_date = ?3, status = ?4, progress_percent = ?5,
+       deadline_at = ?6, deadline_all_day = ?7, is_recurring = ?8, recurrence_type = ?9,
+       recurrence_interval = ?10, recurrence_weekdays = ?11, timer_enabled = ?12, timer_minutes = ?13,
+       timer_state = ?14, timer_ends_at = NULL, rolled_over = 0, rolled_from_date = NULL, tags = ?15,
+       sort_order = ?16, reminder_at = ?17, reminder_state = ?18, updated_at = ?19, catchup_policy = ?22,
+       recurrence_paused = ?23, recurrence_reset_notes = ?24, recurrence_keep_missed = ?25,
+       timer_seconds = ?26, project_id = ?27,
+       snooze_until = CASE WHEN status != ?4 THEN NULL ELSE snooze_until END,
+       done_at = CASE WHEN ?4 != 'done' THEN NULL WHEN status = 'done' THEN done_at ELSE ?19 END
+       WHERE id = ?20 AND (?21 IS NULL OR updated_at = ?21)",
+      params![
+        input.title,
+        input.notes,
+        target_date,
+        input.status,
+        input.progress_percent.clamp(0, 100),
+        deadline_at,
+        deadline_all_day,
+        is_recurring,
+        recurrence_type,
+        recurrence_interval,
+        recurrence_weekdays,
+        timer_enabled,
+        timer_minutes,
+        timer_state,
+        tags_csv,
+        sort_order,
+        reminder_at,
+        reminder_state,
+        now,
+        id,
+        expected_updated_at,
+        catchup_policy,
+        is_recurring == 1 && recurrence_paused,
+        is_recurring == 1 && input.recurrence_reset_notes,
+        is_recurring == 1 && input.recurrence_keep_missed,
+        timer_seconds,
+        project_id
+      ],
+    )
+    .map_err(|err| err.to_string())?;
+    ensure_row_updated(changed, expected_updated_at)?;
+    sync_task_tags(conn, id, &normalized_tags)?;
+    record_progress(
+        conn,
+        id,
+        existing.progress_percent,
+        input.progress_percent.clamp(0, 100),
+        &now,
+    )?;
+    if existing.recurrence_paused && !recurrence_paused {
+        skip_missed_occurrences(conn, id, today_value)?;
+    }
+    get_task(conn, id)
+}
+
+/// Writes only the fields present in `patch`, so inline edits cannot clobber values the
+/// caller never saw. Unlike `update_task`, rollover and timer state are left alone
+/// unless the target date changes.
+pub fn patch_task(conn: &Connection, id: &str, patch: TaskPatch) -> Result<Task, String> {
+    let existing = get_task(conn, id)?;
+    let now = Utc::now().to_rfc3339();
+    let mut changes: Vec<(&str, Value)> = Vec::new();
+
+    if let Some(title) = &patch.title {
+        let title = title.trim();
+        if title.is_empty() {
+            return Err("Title is required".to_string());
+        }
+        changes.push(("title", Value::Text(title.to_string())));
+    }
+    if let Some(notes) = &patch.notes {
+        let notes = notes.trim();
+        let notes = if notes.is_empty() {
+            Value::Null
+        } else {
+            Value::Text(notes.to_string())
+        };
+        changes.push(("notes", notes));
+    }
+    let tags = patch.tags.as_deref().map(normalize_task_tags);
+    if let Some(tags) = &tags {
+        changes.push(("tags", Value::Text(normalize_tags(tags))));
+    }
+    if let Some(target_date) = &patch.target_date {
+        let target_date = normalize_target_date(Some(target_date));
+        if target_date != INBOX_DATE {
+            parse_date(&target_date)?;
+        }
+        if target_date != existing.target_date {
+            let sort_order = next_sort_order(conn, &target_date, 0)?;
+            changes.push(("target_date", Value::Text(target_date)));
+            changes.push(("sort_order", Value::Integer(sort_order)));
+            changes.push(("rolled_over", Value::Integer(0)));
+            changes.push(("rolled_from_date", Value::Null));
+        }
+    }
+    if let Some(status) = &patch.status {
+        if !TASK_STATUSES.contains(&status.as_str()) {
+            return Err(format!("Invalid status: {}", status));
+        }
+    }
+    let progress = patch
+        .progress_percent
+        .map(|progress| progress.clamp(0, 100));
+    if let Some(progress) = progress {
+        changes.push(("progress_percent", Value::Integer(progress.into())));
+    }
+    if patch.deadline_at.is_some() || patch.deadline_all_day.is_some() {
+        let deadline_at = patch
+            .deadline_at
+            .as_deref()
+            .or(existing.deadline_at.as_deref());
+        let all_day = patch
+            .deadline_all_day
+            .unwrap_or(patch.deadline_at.is_none() && existing.deadline_all_day);
+        let (deadline_at, deadline_all_day) = normalize_deadline(deadline_at, all_day)?;
+        changes.push(("deadline_at", deadline_at.map_or(Value::Null, Value::Text)));
+        changes.push(("deadline_all_day", Value::Integer(deadline_all_day.into())));
+    }
+
+    if changes.is_empty() && patch.status.is_none() {
+        return Ok(existing);
+    }
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|err| err.to_string())?;
+    if !changes.is_empty() {
+        changes.push(("updated_at", Value::Text(now.clone())));
+        let assignments = changes
+            .iter()
+            .enumerate()
+            .map(|(index, (column, _))| format!("{} = ?{}", column, index + 1))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut values: Vec<Value> = changes.into_iter().map(|(_, value)| value).collect();
+        values.push(Value::Text(id.to_string()));
+        let sql = format!(
+            "UPDATE tasks SET {} WHERE id = ?{}",
+            assignments,
+            values.len()
+        );
+        tx.execute(&sql, params_from_iter(values))
+            .map_err(|err| err.to_string())?;
+    }
+    if let Some(tags) = &tags {
+        sync_task_tags(&tx, id, tags)?;
+    }
+    if let Some(progress) = progress {
+        record_progress(&tx, id, existing.progress_percent, progress, &now)?;
+    }
+    // Status goes through `update_status` so done tasks get the same bucket move and
+    // reminder cleanup as the status menu.
+    if let Some(status) = &patch.status {
+        update_status(&tx, id, status)?;
+    }
+    tx.commit().map_err(|err| err.to_string())?;
+    get_task(conn, id)
+}
+
+/// Appends a point to the task's progress history when the percent actually changed.
+fn record_progress(
+    conn: &Connection,
+    task_id: &str,
+    previous: i32,
+    percent: i32,
+    recorded_at: &str,
+) -> Result<(), String> {
+    if previous == percent {
+        return Ok(());
+    }
+    conn.execute(
+        "INSERT INTO task_progress_history (task_id, percent, recorded_at) VALUES (?1, ?2, ?3)",
+        params![task_id, percent, recorded_at],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+pub fn task_progress_history(
+    conn: &Connection,
+    task_id: &str,
+) -> Result<Vec<ProgressPoint>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT percent, recorded_at FROM task_progress_history
+       WHERE task_id = ?1 ORDER BY recorded_at ASC, rowid ASC",
+        )
+        .map_err(|err| err.to_string())?;
+    let points = stmt
+        .query_map(params![task_id], |row| {
+            Ok(ProgressPoint {
+                percent: row.get(0)?,
+                recorded_at: row.get(1)?,
+            })
+        })
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    Ok(points)
+}
+
+pub fn bulk_add_tag(conn: &Connection, task_ids: &[String], tag: &str) -> Result<usize, String> {
+    bulk_edit_tag(conn, task_ids, tag, true)
+}
+
+pub fn bulk_remove_tag(conn: &Connection, task_ids: &[String], tag: &str) -> Result<usize, String> {
+    bulk_edit_tag(conn, task_ids, tag, false)
+}
+
+/// Adds or removes one tag (case-insensitively) on every listed task in a single
+/// transaction. Returns how many tasks actually changed.
+fn bulk_edit_tag(
+    conn: &Connection,
+    task_ids: &[String],
+    tag: &str,
+    add: bool,
+) -> Result<usize, String> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Err("Tag is required".to_string());
+    }
+    if tag.contains(',') {
+        return Err("Tags cannot contain commas".to_string());
+    }
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|err| err.to_string())?;
+    let now = Utc::now().to_rfc3339();
+    let mut affected = 0;
+    for id in task_ids {
+        let task = get_task(&tx, id)?;
+        let has_tag = task
+            .tags
+            .iter()
+            .any(|existing| existing.eq_ignore_ascii_case(tag));
+        if has_tag == add {
+            continue;
+        }
+        let mut tags = task.tags;
+        if add {
+            tags.push(tag.to_string());
+        } else {
+            tags.retain(|existing| !existing.eq_ignore_ascii_case(tag));
+        }
+        let tags = normalize_task_tags(&tags);
+        tx.execute(
+            "UPDATE tasks SET tags = ?1, updated_at = ?2 WHERE id = ?3",
+            params![normalize_tags(&tags), now, id],
+        )
+        .map_err(|err| err.to_string())?;
+        sync_task_tags(&tx, id, &tags)?;
+        affected += 1;
+    }
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(affected)
+}
+
+pub fn update_status(conn: &Connection, id: &str, status: &str) -> Result<Task, String> {
+    let now = Utc::now().to_rfc3339();
+    let task = get_task(conn, id)?;
+    if status == "done" {
+        let bucket = if task.rolled_over { 1 } else { 0 };
+        let new_sort_order = next_sort_order(conn, &task.target_date, bucket)?;
+        conn.execute(
+            "UPDATE tasks SET status = ?1, sort_order = ?2, reminder_at = NULL, reminder_state = NULL,
+             snooze_until = NULL, done_at = CASE WHEN status = 'done' THEN done_at ELSE ?3 END,
+             updated_at = ?3 WHERE id = ?4",
+            params![status, new_sort_order, now, id],
+        )
+        .map_err(|err| err.to_string())?;
+    } else {
+        conn.execute(
+            "UPDATE tasks SET status = ?1, done_at = NULL, snooze_until = NULL, updated_at = ?2
+             WHERE id = ?3",
+            params![status, now, id],
+        )
+        .map_err(|err| err.to_string())?;
+    }
+    get_task(conn, id)
+}
+
+pub fn set_status(conn: &Connection, id: &str, status: &str) -> Result<Task, String> {
+    update_status(conn, id, status)
+}
+
+pub fn mark_done_and_generate_next(conn: &Connection, id: &str) -> Result<Task, String> {
+    let task = update_status(conn, id, "done")?;
+    if task.is_recurring && !task.recurrence_paused {
+        let base = parse_date(&task.target_date)?;
+        let next = next_occurrence_date(&task, base);
+        let next_str = next.format("%Y-%m-%d").to_string();
+        if !has_recurring_occurrence(conn, &task, &next_str)? {
+            insert_next_occurrence(conn, &task, &next_str)?;
+        }
+    }
+    get_task(conn, id)
+}
+
+/// Hides an open task from today's list until `until`, after which it reappears.
+pub fn snooze_task(
+    conn: &Connection,
+    id: &str,
+    until: &str,
+    now: DateTime<Utc>,
+) -> Result<Task, String> {
+    let until = DateTime::parse_from_rfc3339(until.trim())
+        .map_err(|_| format!("Invalid snooze time: {}", until.trim()))?
+        .with_timezone(&Utc);
+    if until <= now {
+        return Err("Snooze time must be in the future".to_string());
+    }
+    if get_task(conn, id)?.status == "done" {
+        return Err("Done tasks cannot be snoozed".to_string());
+    }
+    conn.execute(
+        "UPDATE tasks SET snooze_until = ?1, updated_at = ?2 WHERE id = ?3",
+        params![snooze_stamp(until), Utc::now().to_rfc3339(), id],
+    )
+    .map_err(|err| err.to_string())?;
+    get_task(conn, id)
+}
+
+/// Clears snoozes that have run out; returns how many tasks resurfaced.
+pub fn clear_expired_snoozes(conn: &Connection, now: DateTime<Utc>) -> Result<usize, String> {
+    conn.execute(
+        "UPDATE tasks SET snooze_until = NULL WHERE snooze_until IS NOT NULL AND snooze_until <= ?1",
+        params![snooze_stamp(now)],
+    )
+    .map_err(|err| err.to_string())
+}
+
+/// Snooze times are stored in one fixed UTC format so SQL can compare them as text.
+fn snooze_stamp(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+pub fn get_task(conn: &Connection, id: &str) -> Result<Task, String> {
+    let mut stmt = conn
+        .prepare("SELECT * FROM tasks WHERE id = ?1")
+        .map_err(|err| err.to_string())?;
+    let task = stmt
+        .query_row(params![id], map_task_row)
+        .map_err(|err| err.to_string())?;
+    Ok(task)
+}
+
+/// Starts a timer session, or extends the current one when the task's timer is already
+/// running. A session left open by a crash is closed as not completed first.
+pub fn start_timer(conn: &Connection, id: &str, ends_at: &str) -> Result<(), String> {
+    let ends_at_time = DateTime::parse_from_rfc3339(ends_at).map_err(|err| err.to_string())?;
+    let now = Utc::now();
+    let now_value = now.to_rfc3339();
+    let task = get_task(conn, id)?;
+    let open = open_timer_session(conn, id)?;
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|err| err.to_string())?;
+    match open {
+        Some(session) if task.timer_state.as_deref() == Some("running") => {
+            let started_at =
+                DateTime::parse_from_rfc3339(&session.started_at).map_err(|err| err.to_string())?;
+            tx.execute(
+                "UPDATE timer_sessions SET planned_seconds = ?1 WHERE id = ?2",
+                params![(ends_at_time - started_at).num_seconds().max(0), session.id],
+            )
+            .map_err(|err| err.to_string())?;
+        }
+        open => {
+            if let Some(stale) = open {
+                close_timer_session(&tx, &stale.id, &now_value, false)?;
+            }
+            tx.execute(
+                "INSERT INTO timer_sessions (id, task_id, started_at, planned_seconds, ended_at, completed)
+         VALUES (?1, ?2, ?3, ?4, NULL, 0)",
+                params![
+                    Uuid::new_v4().to_string(),
+                    id,
+                    now_value,
+                    (ends_at_time.with_timezone(&Utc) - now).num_seconds().max(0)
+                ],
+            )
+            .map_err(|err| err.to_string())?;
+        }
+    }
+    tx.execute(
+        "UPDATE tasks SET timer_state = 'running', timer_ends_at = ?1, updated_at = ?2 WHERE id = ?3",
+        params![ends_at, now_value, id],
+    )
+    .map_err(|err| err.to_string())?;
+    tx.commit().map_err(|err| err.to_string())
+}
+
+pub fn finish_timer(conn: &Connection, id: &str) -> Result<(), String> {
+    end_timer(conn, id, "finished", true)
+}
+
+pub fn stop_timer(conn: &Connection, id: &str) -> Result<(), String> {
+    end_timer(conn, id, "paused", false)
+}
+
+fn end_timer(
+    conn: &Connection,
+    id: &str,
+    timer_state: &str,
+    completed: bool,
+) -> Result<(), String> {
+    let now = Utc::now().to_rfc3339();
+    let open = open_timer_session(conn, id)?;
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|err| err.to_string())?;
+    if let Some(session) = open {
+        close_timer_session(&tx, &session.id, &now, completed)?;
+    }
+    tx.execute(
+        "UPDATE tasks SET timer_state = ?1, timer_ends_at = NULL, updated_at = ?2 WHERE id = ?3",
+        params![timer_state, now, id],
+    )
+    .map_err(|err| err.to_string())?;
+    tx.commit().map_err(|err| err.to_string())
+}
+
+fn open_timer_session(conn: &Connection, task_id: &str) -> Result<Option<TimerSession>, String> {
+    conn.query_row(
+        "SELECT * FROM timer_sessions WHERE task_id = ?1 AND ended_at IS NULL
+       ORDER BY started_at DESC LIMIT 1",
+        params![task_id],
+        map_timer_session_row,
+    )
+    .optional()
+    .map_err(|err| err.to_string())
+}
+
+fn close_timer_session(
+    conn: &Connection,
+    session_id: &str,
+    ended_at: &str,
+    completed: bool,
+) -> Result<(), String> {
+    conn.execute(
+        "UPDATE timer_sessions SET ended_at = ?1, completed = ?2 WHERE id = ?3",
+        params![ended_at, completed, session_id],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+pub fn list_timer_sessions(conn: &Connection, task_id: &str) -> Result<Vec<TimerSession>, String> {
+    let mut stmt = conn
+        .prepare("SELECT * FROM timer_sessions WHERE task_id = ?1 ORDER BY started_at DESC")
+        .map_err(|err| err.to_string())?;
+    let sessions = stmt
+        .query_map(params![task_id], map_timer_session_row)
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    Ok(sessions)
+}
+
+pub fn delete_task(conn: &Connection, id: &str) -> Result<(), String> {
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|err| err.to_string())?;
+
+    tx.execute(
+        "UPDATE notes SET task_id = NULL WHERE task_id = ?1",
+        params![id],
+    )
+    .map_err(|err| err.to_string())?;
+
+    tx.execute("DELETE FROM tasks WHERE id = ?1", params![id])
+        .map_err(|err| err.to_string())?;
+
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+/// Re-inserts a deleted task under its original id. Links that were dropped with it
+/// (dependencies, notes pointing at it) are not restored, and a running timer comes back
+/// idle.
+pub fn restore_task(conn: &Connection, task: &Task) -> Result<Task, String> {
+    let tags = normalize_task_tags(&task.tags);
+    let timer_state = if task.timer_enabled {
+        Some("idle")
+    } else {
+        None
+    };
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|err| err.to_string())?;
+    tx.execute(
+        "INSERT INTO tasks (id, title, notes, tags, target_date, status, progress_percent, deadline_at,
+       deadline_all_day, is_recurring, recurrence_type, recurrence_interval, recurrence_weekdays,
+       catchup_policy, recurrence_paused, timer_enabled, timer_minutes, timer_state, timer_ends_at,
+       rolled_over, rolled_from_date, rollover_count, sort_order, archived, reminder_at, reminder_state,
+       done_at, created_at, updated_at, recurrence_reset_notes, recurrence_keep_missed,
+       source_checkin_id, timer_seconds, project_id, snooze_until)
+       VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, NULL,
+       ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30,
+       (SELECT id FROM checkins WHERE id = ?31), ?32, (SELECT id FROM projects WHERE id = ?33),
+       ?34)",
+        params![
+            task.id,
+            task.title,
+            task.notes,
+            normalize_tags(&tags),
+            task.target_date,
+            task.status,
+            task.progress_percent,
+            task.deadline_at,
+            task.deadline_all_day,
+            task.is_recurring,
+            task.recurrence_type,
+            task.recurrence_interval,
+            task.recurrence_weekdays,
+            task.catchup_policy,
+            task.recurrence_paused,
+            task.timer_enabled,
+            task.timer_minutes,
+            timer_state,
+            task.rolled_over,
+            task.rolled_from_date,
+            task.rollover_count,
+            task.sort_order,
+            task.archived,
+            task.reminder_at,
+            task.reminder_state,
+            task.done_at,
+            task.created_at,
+            Utc::now().to_rfc3339(),
+            task.recurrence_reset_notes,
+            task.recurrence_keep_missed,
+            task.source_checkin_id,
+            task.timer_seconds,
+            task.project_id,
+            task.snooze_until
+        ],
+    )
+    .map_err(|err| err.to_string())?;
+    sync_task_tags(&tx, &task.id, &tags)?;
+    tx.commit().map_err(|err| err.to_string())?;
+    get_task(conn, &task.id)
+}
+
+pub fn task_detail(conn: &Connection, id: &str) -> Result<TaskDetail, String> {
+    let task = get_task(conn, id)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT g.name FROM task_tags tt
+       INNER JOIN tags g ON g.id = tt.tag_id
+       WHERE tt.task_id = ?1
+       ORDER BY lower(g.name) ASC",
+        )
+        .map_err(|err| err.to_string())?;
+    let tags = stmt
+        .query_map(params![id], |row| row.get(0))
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    Ok(TaskDetail {
+        dependencies: list_task_dependencies(conn, id)?,
+        notes: list_notes_for_task(conn, id)?,
+        task,
+        tags,
+    })
+}
+
+pub fn list_task_dependencies(conn: &Connection, task_id: &str) -> Result<Vec<Task>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.* FROM task_dependencies d
+       INNER JOIN tasks t ON t.id = d.depends_on_id
+       WHERE d.task_id = ?1
+       ORDER BY t.target_date ASC, t.sort_order ASC",
+        )
+        .map_err(|err| err.to_string())?;
+    let tasks = stmt
+        .query_map(params![task_id], map_task_row)
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    Ok(tasks)
+}
+
+fn depends_on_ids(conn: &Connection, task_id: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT depends_on_id FROM task_dependencies WHERE task_id = ?1")
+        .map_err(|err| err.to_string())?;
+    let ids = stmt
+        .query_map(params![task_id], |row| row.get(0))
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    Ok(ids)
+}
+
+pub fn add_task_dependency(
+    conn: &Connection,
+    task_id: &str,
+    depends_on_id: &str,
+) -> Result<Vec<Task>, String> {
+    if task_id == depends_on_id {
+        return Err("A task cannot depend on itself".to_string());
+    }
+    let _ = get_task(conn, task_id)?;
+    let _ = get_task(conn, depends_on_id)?;
+
+    // Walk everything the new blocker already depends on; reaching task_id means a cycle.
+    let mut visited = HashSet::new();
+    let mut stack = vec![depends_on_id.to_string()];
+    while let Some(current) = stack.pop() {
+        if current == task_id {
+            return Err("Dependency would create a cycle".to_string());
+        }
+        if visited.insert(current.clone()) {
+            stack.extend(depends_on_ids(conn, &current)?);
+        }
+    }
+
+    conn.execute(
+        "INSERT OR IGNORE INTO task_dependencies (task_id, depends_on_id, created_at)
+       VALUES (?1, ?2, ?3)",
+        params![task_id, depends_on_id, Utc::now().to_rfc3339()],
+    )
+    .map_err(|err| err.to_string())?;
+    list_task_dependencies(conn, task_id)
+}
+
+pub fn remove_task_dependency(
+    conn: &Connection,
+    task_id: &str,
+    depends_on_id: &str,
+) -> Result<Vec<Task>, String> {
+    conn.execute(
+        "DELETE FROM task_dependencies WHERE task_id = ?1 AND depends_on_id = ?2",
+        params![task_id, depends_on_id],
+    )
+    .map_err(|err| err.to_string())?;
+    list_task_dependencies(conn, task_id)
+}
+
+pub fn ensure_unblocked(conn: &Connection, task_id: &str) -> Result<(), String> {
+    let open: Vec<String> = list_task_dependencies(conn, task_id)?
+        .into_iter()
+        .filter(|task| task.status != "done")
+        .map(|task| task.title)
+        .collect();
+    if open.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("{}: {}", BLOCKED_BY_DEPENDENCIES, open.join(", ")))
+    }
+}
+
+pub fn move_task(conn: &Connection, id: &str, direction: &str) -> Result<(), String> {
+    let (target_date, rolled_over, sort_order): (String, i32, i64) = conn
+        .query_row(
+            "SELECT target_date, rolled_over, sort_order FROM tasks WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|err| err.to_string())?;
+
+    let (comparison, sort_direction) = match direction {
+        "up" => ("<", "DESC"),
+        "down" => (">", "ASC"),
+        _ => return Err("Invalid move direction".to_string()),
+    };
+
+    let sql = format!(
+        "SELECT id, sort_order FROM tasks
+     WHERE target_date = ?1 AND rolled_over = ?2 AND id != ?3 AND sort_order {} ?4
+     ORDER BY sort_order {} LIMIT 1",
+        comparison, sort_direction
+    );
+
+    let neighbor: Option<(String, i64)> = conn
+        .query_row(
+            &sql,
+            params![target_date, rolled_over, id, sort_order],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+
+    if let Some((neighbor_id, neighbor_sort_order)) = neighbor {
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "UPDATE tasks SET sort_order = ?1, updated_at = ?2 WHERE id = ?3",
+            params![neighbor_sort_order, now, id],
+        )
+        .map_err(|err| err.to_string())?;
+        conn.execute(
+            "UPDATE tasks SET sort_order = ?1, updated_at = ?2 WHERE id = ?3",
+            params![sort_order, now, neighbor_id],
+        )
+        .map_err(|err| err.to_string())?;
+    }
+
+    Ok(())
+}
+
+pub fn reorder_tasks(conn: &Connection, task_ids: &[String]) -> Result<(), String> {
+    let now = Utc::now().to_rfc3339();
+    for (index, task_id) in task_ids.iter().enumerate() {
+        conn.execute(
+            "UPDATE tasks SET sort_order = ?1, updated_at = ?2 WHERE id = ?3",
+            params![(index as i64) + 1, now, task_id],
+        )
+        .map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+/// Moves unfinished past tasks onto `today_value`. `mode` "all" moves every one, "recent"
+/// only yesterday's and "off" none. With `log_in_notes`, each move also appends a dated
+/// line to the task notes. Recurring tasks that keep missed days or catch up on every
+/// missed day stay on their own date for `ensure_recurrences` to handle.
+pub fn rollover_tasks(
+    conn: &Connection,
+    today_value: &str,
+    mode: &str,
+    log_in_notes: bool,
+) -> Result<usize, String> {
+    let since = match mode {
+        "all" => None,
+        "recent" => Some(
+            (parse_date(today_value)? - Duration::days(1))
+                .format("%Y-%m-%d")
+                .to_string(),
+        ),
+        "off" => return Ok(0),
+        _ => return Err(format!("Unknown rollover mode: {}", mode)),
+    };
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, target_date FROM tasks
+             WHERE target_date < ?1 AND target_date != '' AND status != 'done' AND archived = 0
+             AND (?2 IS NULL OR target_date >= ?2)
+             AND NOT (is_recurring = 1 AND recurrence_paused = 0
+                      AND (recurrence_keep_missed = 1 OR catchup_policy = 'all'))",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows: Vec<(String, String)> = stmt
+        .query_map(params![today_value, since], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+
+    let mut count = 0;
+    for (id, from_date) in rows {
+        let position = next_sort_order(conn, today_value, 1)?;
+        conn.execute(
+            "UPDATE tasks SET target_date = ?1, rolled_over = 1,
+         rolled_from_date = COALESCE(rolled_from_date, ?2), sort_order = ?3,
+         rollover_count = rollover_count + 1, updated_at = ?4 WHERE id = ?5",
+            params![
+                today_value,
+                from_date,
+                position,
+                Utc::now().to_rfc3339(),
+                id
+            ],
+        )
+        .map_err(|err| err.to_string())?;
+        if log_in_notes {
+            let line = format!("— rolled over from {}", from_date);
+            conn.execute(
+                "UPDATE tasks SET notes = CASE WHEN notes IS NULL OR notes = '' THEN ?1
+                 ELSE notes || char(10) || ?1 END WHERE id = ?2",
+                params![line, id],
+            )
+            .map_err(|err| err.to_string())?;
+        }
+        count += 1;
+    }
+    if count > 0 && has_duplicate_sort_orders(conn, today_value)? {
+        compact_sort_orders(conn, today_value)?;
+    }
+    Ok(count)
+}
+
+fn has_duplicate_sort_orders(conn: &Connection, date: &str) -> Result<bool, String> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM tasks WHERE target_date = ?1
+         GROUP BY rolled_over, sort_order HAVING COUNT(*) > 1)",
+        params![date],
+        |row| row.get(0),
+    )
+    .map_err(|err| err.to_string())
+}
+
+/// Renumbers each bucket (planned and rolled over) of `date` to 1..N, keeping the current
+/// order. Returns how many tasks got a new `sort_order`.
+pub fn compact_sort_orders(conn: &Connection, date: &str) -> Result<usize, String> {
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|err| err.to_string())?;
+    let rows: Vec<(String, i32, i64)> = {
+        let mut stmt = tx
+            .prepare(
+                "SELECT id, rolled_over, sort_order FROM tasks WHERE target_date = ?1
+                 ORDER BY rolled_over ASC, sort_order ASC, created_at ASC, id ASC",
+            )
+            .map_err(|err| err.to_string())?;
+        let rows = stmt
+            .query_map(params![date], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .map_err(|err| err.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| err.to_string())?;
+        rows
+    };
+    let mut changed = 0;
+    let mut bucket = None;
+    let mut position = 0;
+    for (id, rolled_over, sort_order) in rows {
+        if bucket != Some(rolled_over) {
+            bucket = Some(rolled_over);
+            position = 0;
+        }
+        position += 1;
+        if sort_order != position {
+            tx.execute(
+                "UPDATE tasks SET sort_order = ?1 WHERE id = ?2",
+                params![position, id],
+            )
+            .map_err(|err| err.to_string())?;
+            changed += 1;
+        }
+    }
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(changed)
+}
+
+pub fn clear_rollover(conn: &Connection, id: &str, today_value: &str) -> Result<Task, String> {
+    let task = get_task(conn, id)?;
+    if !task.rolled_over {
+        return Ok(task);
+    }
+    reschedule_task(conn, id, today_value)
+}
+
+pub fn reschedule_task(conn: &Connection, id: &str, target_date: &str) -> Result<Task, String> {
+    let target_date = target_date.trim();
+    let _ = parse_date(target_date)?;
+    let _ = get_task(conn, id)?;
+    let sort_order = next_sort_order(conn, target_date, 0)?;
+    conn.execute(
+        "UPDATE tasks SET target_date = ?1, rolled_over = 0, rolled_from_date = NULL, sort_order = ?2,
+         updated_at = ?3 WHERE id = ?4",
+        params![target_date, sort_order, Utc::now().to_rfc3339(), id],
+    )
+    .map_err(|err| err.to_string())?;
+    get_task(conn, id)
+}
+
+/// Moves every unfinished task on `from_date` to `to_date`. Recurring tasks stay put: their
+/// date anchors the rule and `ensure_recurrences` advances them on its own.
+pub fn defer_unfinished(
+    conn: &Connection,
+    from_date: &str,
+    to_date: &str,
+) -> Result<usize, String> {
+    let from_date = from_date.trim();
+    let to_date = to_date.trim();
+    let _ = parse_date(from_date)?;
+    let _ = parse_date(to_date)?;
+    if from_date == to_date {
+        return Err("Choose a different date to move tasks to".to_string());
+    }
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|err| err.to_string())?;
+    let mut stmt = tx
+        .prepare(
+            "SELECT id FROM tasks
+       WHERE target_date = ?1 AND status != 'done' AND archived = 0 AND is_recurring = 0
+       ORDER BY rolled_over DESC, sort_order ASC, created_at ASC",
+        )
+        .map_err(|err| err.to_string())?;
+    let ids: Vec<String> = stmt
+        .query_map(params![from_date], |row| row.get(0))
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    drop(stmt);
+
+    let now = Utc::now().to_rfc3339();
+    for id in &ids {
+        let sort_order = next_sort_order(&tx, to_date, 0)?;
+        tx.execute(
+            "UPDATE tasks SET target_date = ?1, rolled_over = 0, rolled_from_date = NULL, sort_order = ?2,
+             updated_at = ?3 WHERE id = ?4",
+            params![to_date, sort_order, now, id],
+        )
+        .map_err(|err| err.to_string())?;
+    }
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(ids.len())
+}
+
+pub fn list_inbox(conn: &Connection) -> Result<Vec<Task>, String> {
+    list_by_query(
+        conn,
+        "SELECT * FROM tasks WHERE target_date = ?1 AND archived = 0 ORDER BY sort_order ASC, created_at ASC",
+        INBOX_DATE,
+    )
+}
+
+pub fn schedule_inbox_task(conn: &Connection, id: &str, target_date: &str) -> Result<Task, String> {
+    let task = get_task(conn, id)?;
+    if task.target_date != INBOX_DATE {
+        return Err("Task is already scheduled".to_string());
+    }
+    reschedule_task(conn, id, target_date)
+}
+
+pub fn defer_task(conn: &Connection, id: &str, days: i64) -> Result<Task, String> {
+    if !(1..=365).contains(&days) {
+        return Err("Days must be between 1 and 365".to_string());
+    }
+    let task = get_task(conn, id)?;
+    let next = parse_date(&task.target_date)? + Duration::days(days);
+    reschedule_task(conn, id, &next.format("%Y-%m-%d").to_string())
+}
+
+pub fn ensure_recurrences(conn: &Connection, today_value: &str) -> Result<(), String> {
+    let today_date = parse_date(today_value)?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT * FROM tasks
+       WHERE is_recurring = 1 AND recurrence_paused = 0 AND archived = 0 AND target_date != ''",
+        )
+        .map_err(|err| err.to_string())?;
+
+    let recurring_tasks: Vec<Task> = stmt
+        .query_map([], map_task_row)
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+
+    for task in recurring_tasks {
+        if task.recurrence_type.is_none() {
+            continue;
+        }
+
+        if task.status == "done" {
+            let base = parse_date(&task.target_date)?;
+            let next = next_occurrence_date(&task, base);
+            let next_str = next.format("%Y-%m-%d").to_string();
+            if !has_recurring_occurrence(conn, &task, &next_str)? {
+                insert_next_occurrence(conn, &task, &next_str)?;
+            }
+            continue;
+        }
+
+        let mut date = parse_date(&task.target_date)?;
+        if date >= today_date {
+            continue;
+        }
+
+        if task.recurrence_keep_missed {
+            // Missed rows stay put; only the newest row of the series generates the next one.
+            if has_later_occurrence(conn, &task)? {
+                continue;
+            }
+            while date < today_date {
+                date = next_occurrence_date(&task, date);
+            }
+            let date_str = date.format("%Y-%m-%d").to_string();
+            if !has_recurring_occurrence(conn, &task, &date_str)? {
+                insert_next_occurrence(conn, &task, &date_str)?;
+            }
+            continue;
+        }
+
+        while date < today_date {
+            // Missed occurrences become plain tasks so only the original keeps advancing.
+            if task.catchup_policy == "all" {
+                let missed = date.format("%Y-%m-%d").to_string();
+                let missed_id = insert_next_occurrence(conn, &task, &missed)?;
+                conn.execute(
+                    "UPDATE tasks SET is_recurring = 0 WHERE id = ?1",
+                    params![missed_id],
+                )
+                .map_err(|err| err.to_string())?;
+            }
+            date = next_occurrence_date(&task, date);
+        }
+
+        let date_str = date.format("%Y-%m-%d").to_string();
+        conn.execute(
+            "UPDATE tasks SET target_date = ?1, sort_order = ?2, updated_at = ?3 WHERE id = ?4",
+            params![
+                date_str,
+                next_sort_order(conn, &date_str, if task.rolled_over { 1 } else { 0 })?,
+                Utc::now().to_rfc3339(),
+                task.id
+            ],
+        )
+        .map_err(|err| err.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Deletes future, unfinished siblings of a recurring task (matched by `SAME_SERIES`),
+/// unlinking their notes, and regenerates the next one from `today_value` with the
+/// task's current rule. An unfinished task dated today or later stays the live occurrence.
+pub fn reset_recurrence_series(
+    conn: &Connection,
+    task_id: &str,
+    today_value: &str,
+) -> Result<SeriesReset, String> {
+    let today_date = parse_date(today_value)?;
+    let task = get_task(conn, task_id)?;
+    if !task.is_recurring || task.recurrence_type.is_none() {
+        return Err("Task is not recurring".to_string());
+    }
+
+    let doomed = format!(
+        "{} AND id != ?5 AND target_date > ?6 AND status != 'done'",
+        SAME_SERIES
+    );
+    let series_params = params![
+        task.title,
+        task.recurrence_type,
+        task.recurrence_interval,
+        task.recurrence_weekdays,
+        task.id,
+        today_value
+    ];
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|err| err.to_string())?;
+    tx.execute(
+        &format!(
+            "UPDATE notes SET task_id = NULL WHERE task_id IN (SELECT id FROM tasks WHERE {})",
+            doomed
+        ),
+        series_params,
+    )
+    .map_err(|err| err.to_string())?;
+    let deleted = tx
+        .execute(
+            &format!("DELETE FROM tasks WHERE {}", doomed),
+            series_params,
+        )
+        .map_err(|err| err.to_string())?;
+
+    let next_date = if task.recurrence_paused {
+        None
+    } else if task.status != "done" && task.target_date.as_str() >= today_value {
+        Some(task.target_date.clone())
+    } else {
+        let next = next_occurrence_date(&task, today_date)
+            .format("%Y-%m-%d")
+            .to_string();
+        if !has_recurring_occurrence(&tx, &task, &next)? {
+            insert_next_occurrence(&tx, &task, &next)?;
+        }
+        Some(next)
+    };
+    tx.commit().map_err(|err| err.to_string())?;
+
+    Ok(SeriesReset { deleted, next_date })
+}
+
+/// Every occurrence of a recurring task's series (matched by `SAME_SERIES`), past and future, archived included, by `target_date`.
+pub fn recurring_history(conn: &Connection, task_id: &str) -> Result<Vec<Task>, String> {
+    let task = get_task(conn, task_id)?;
+    if !task.is_recurring {
+        return Err("Task is not recurring".to_string());
+    }
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT * FROM tasks WHERE {} ORDER BY target_date ASC, created_at ASC",
+            SAME_SERIES
+        ))
+        .map_err(|err| err.to_string())?;
+    let tasks = stmt
+        .query_map(
+            params![
+                task.title,
+                task.recurrence_type,
+                task.recurrence_interval,
+                task.recurrence_weekdays
+            ],
+            map_task_row,
+        )
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    Ok(tasks)
+}
+
+/// Pauses or resumes generation for a recurring task. Resuming moves an unfinished task
+/// straight to its next date on or after `today_value`; occurrences missed while paused
+/// are never created, whatever the catch-up policy.
+pub fn set_recurrence_paused(
+    conn: &Connection,
+    id: &str,
+    paused: bool,
+    today_value: &str,
+) -> Result<Task, String> {
+    let task = get_task(conn, id)?;
+    if !task.is_recurring {
+        return Err("Task is not recurring".to_string());
+    }
+    if task.recurrence_paused == paused {
+        return Ok(task);
+    }
+    conn.execute(
+        "UPDATE tasks SET recurrence_paused = ?1, updated_at = ?2 WHERE id = ?3",
+        params![paused, Utc::now().to_rfc3339(), id],
+    )
+    .map_err(|err| err.to_string())?;
+    if !paused {
+        skip_missed_occurrences(conn, id, today_value)?;
+    }
+    get_task(conn, id)
+}
+
+fn skip_missed_occurrences(conn: &Connection, id: &str, today_value: &str) -> Result<(), String> {
+    let task = get_task(conn, id)?;
+    if !task.is_recurring || task.status == "done" || task.target_date == INBOX_DATE {
+        return Ok(());
+    }
+    let today_date = parse_date(today_value)?;
+    let mut date = parse_date(&task.target_date)?;
+    if date >= today_date {
+        return Ok(());
+    }
+    while date < today_date {
+        date = next_occurrence_date(&task, date);
+    }
+    let date_str = date.format("%Y-%m-%d").to_string();
+    conn.execute(
+        "UPDATE tasks SET target_date = ?1, sort_order = ?2, rolled_over = 0, rolled_from_date = NULL,
+         updated_at = ?3 WHERE id = ?4",
+        params![
+            date_str,
+            next_sort_order(conn, &date_str, 0)?,
+            Utc::now().to_rfc3339(),
+            id
+        ],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+pub fn archive_done_before(conn: &Connection, before: &str) -> Result<usize, String> {
+    let _ = parse_date(before)?;
+    conn.execute(
+        "UPDATE tasks SET archived = 1, updated_at = ?1
+       WHERE status = 'done' AND archived = 0 AND target_date < ?2 AND target_date != ''",
+        params![Utc::now().to_rfc3339(), before],
+    )
+    .map_err(|err| err.to_string())
+}
+
+/// Bulk cleanup of every done task dated before `before`, archived or not. `mode` is
+/// "delete" (the default) or "archive". Timers are reset and notes pointing at deleted
+/// tasks are unlinked; tags, dependencies and sessions go with the rows. Returns the ids
+/// affected so callers can drop in-memory timer state.
+pub fn delete_done_before(
+    conn: &Connection,
+    before: &str,
+    mode: Option<&str>,
+) -> Result<Vec<String>, String> {
+    let before = before.trim();
+    let _ = parse_date(before)?;
+    let delete = match mode.map(str::trim).filter(|value| !value.is_empty()) {
+        None | Some("delete") => true,
+        Some("archive") => false,
+        Some(other) => return Err(format!("Invalid clear mode: {}", other)),
+    };
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|err| err.to_string())?;
+    let ids: Vec<String> = {
+        let mut stmt = tx
+            .prepare(
+                "SELECT id FROM tasks WHERE status = 'done' AND target_date < ?1 AND target_date != ''",
+            )
+            .map_err(|err| err.to_string())?;
+        let ids = stmt
+            .query_map(params![before], |row| row.get(0))
+            .map_err(|err| err.to_string())?
+            .filter_map(Result::ok)
+            .collect();
+        ids
+    };
+    if delete {
+        tx.execute(
+            "UPDATE notes SET task_id = NULL WHERE task_id IN
+       (SELECT id FROM tasks WHERE status = 'done' AND target_date < ?1 AND target_date != '')",
+            params![before],
+        )
+        .map_err(|err| err.to_string())?;
+        tx.execute(
+            "DELETE FROM tasks WHERE status = 'done' AND target_date < ?1 AND target_date != ''",
+            params![before],
+        )
+        .map_err(|err| err.to_string())?;
+    } else {
+        tx.execute(
+            "UPDATE tasks SET archived = 1, timer_ends_at = NULL,
+         timer_state = CASE WHEN timer_enabled = 1 THEN 'idle' END, updated_at = ?1
+       WHERE status = 'done' AND target_date < ?2 AND target_date != ''",
+            params![Utc::now().to_rfc3339(), before],
+        )
+        .map_err(|err| err.to_string())?;
+    }
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(ids)
+}
+
+/// End-of-day cleanup of the done tasks on `date`. `mode` is "archive" (the default)
+/// or "delete"; returns the ids affected so callers can drop in-memory timer state.
+pub fn clear_done(
+    conn: &Connection,
+    date: &str,
+    mode: Option<&str>,
+) -> Result<Vec<String>, String> {
+    let date = date.trim();
+    let _ = parse_date(date)?;
+    let delete = match mode.map(str::trim).filter(|value| !value.is_empty()) {
+        None | Some("archive") => false,
+        Some("delete") => true,
+        Some(other) => return Err(format!("Invalid clear mode: {}", other)),
+    };
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|err| err.to_string())?;
+    let ids: Vec<String> = {
+        let sql = if delete {
+            "SELECT id FROM tasks WHERE target_date = ?1 AND status = 'done'"
+        } else {
+            "SELECT id FROM tasks WHERE target_date = ?1 AND status = 'done' AND archived = 0"
+        };
+        let mut stmt = tx.prepare(sql).map_err(|err| err.to_string())?;
+        let ids = stmt
+            .query_map(params![date], |row| row.get(0))
+            .map_err(|err| err.to_string())?
+            .filter_map(Result::ok)
+            .collect();
+        ids
+    };
+    if delete {
+        tx.execute(
+            "UPDATE notes SET task_id = NULL WHERE task_id IN
+       (SELECT id FROM tasks WHERE target_date = ?1 AND status = 'done')",
+            params![date],
+        )
+        .map_err(|err| err.to_string())?;
+        tx.execute(
+            "DELETE FROM tasks WHERE target_date = ?1 AND status = 'done'",
+            params![date],
+        )
+        .map_err(|err| err.to_string())?;
+    } else {
+        tx.execute(
+            "UPDATE tasks SET archived = 1, updated_at = ?1
+       WHERE target_date = ?2 AND status = 'done' AND archived = 0",
+            params![Utc::now().to_rfc3339(), date],
+        )
+        .map_err(|err| err.to_string())?;
+    }
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(ids)
+}
+
+pub fn list_archived_tasks(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<Task>, String> {
+    let _ = parse_date(start_date)?;
+    let _ = parse_date(end_date)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT * FROM tasks
+       WHERE archived = 1 AND target_date >= ?1 AND target_date <= ?2
+       ORDER BY target_date DESC, sort_order ASC, created_at ASC",
+        )
+        .map_err(|err| err.to_string())?;
+    let tasks = stmt
+        .query_map(params![start_date, end_date], map_task_row)
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    Ok(tasks)
+}
+
+pub fn unarchive_task(conn: &Connection, id: &str) -> Result<Task, String> {
+    let updated = conn
+        .execute(
+            "UPDATE tasks SET archived = 0, updated_at = ?1 WHERE id = ?2",
+            params![Utc::now().to_rfc3339(), id],
+        )
+        .map_err(|err| err.to_string())?;
+    if updated == 0 {
+        return Err("Task not found".to_string());
+    }
+    get_task(conn, id)
+}
+
+pub fn list_notes(conn: &Connection) -> Result<Vec<Note>, String> {
+    let mut stmt = conn
+    .prepare("SELECT id, title, body_markdown, tags, folder_id, task_id, color, icon, sort_order, created_at,
+       updated_at FROM notes ORDER BY updated_at DESC")
+    .map_err(|err| err.to_string())?;
+    let notes = stmt
+        .query_map([], map_note_row)
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    Ok(notes)
+}
+
+fn sync_note_links(conn: &Connection, note_id: &str, body_markdown: &str) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM note_links WHERE source_id = ?1",
+        params![note_id],
+    )
+    .map_err(|err| err.to_string())?;
+    for target in extract_wikilinks(body_markdown) {
+        conn.execute(
+            "INSERT OR IGNORE INTO note_links (source_id, target_title, target_id) VALUES (?1, ?2, NULL)",
+            params![note_id, target],
+        )
+        .map_err(|err| err.to_string())?;
+    }
+    resolve_note_links(conn)
+}
+
+/// Points every link at the oldest note whose title matches, so renames and deletes are
+/// picked up by links written before them.
+fn resolve_note_links(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "UPDATE note_links SET target_id = (
+         SELECT id FROM notes WHERE lower(notes.title) = lower(note_links.target_title)
+         ORDER BY created_at ASC LIMIT 1
+       )",
+        [],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+pub fn list_backlinks(conn: &Connection, note_id: &str) -> Result<Vec<Note>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, title, body_markdown, tags, folder_id, task_id, color, icon, sort_order, created_at,
+       updated_at FROM notes
+       WHERE id IN (SELECT source_id FROM note_links WHERE target_id = ?1)
+       ORDER BY updated_at DESC",
+        )
+        .map_err(|err| err.to_string())?;
+    let notes = stmt
+        .query_map(params![note_id], map_note_row)
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    Ok(notes)
+}
+
+pub fn list_notes_for_task(conn: &Connection, task_id: &str) -> Result<Vec<Note>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, title, body_markdown, tags, folder_id, task_id, color, icon, sort_order, created_at,
+       updated_at FROM notes
+       WHERE task_id = ?1 ORDER BY updated_at DESC",
+        )
+        .map_err(|err| err.to_string())?;
+    let notes = stmt
+        .query_map(params![task_id], map_note_row)
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    Ok(notes)
+}
+
+pub fn list_notes_in_folder(
+    conn: &Connection,
+    folder_id: Option<&str>,
+) -> Result<Vec<Note>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, title, body_markdown, tags, folder_id, task_id, color, icon, sort_order, created_at,
+       updated_at FROM notes
+       WHERE folder_id IS ?1 ORDER BY sort_order ASC, created_at ASC",
+        )
+        .map_err(|err| err.to_string())?;
+    let notes = stmt
+        .query_map(params![folder_id], map_note_row)
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    Ok(notes)
+}
+
+/// Manual order only; `updated_at` is left alone so the "all notes" view does not reshuffle.
+pub fn reorder_notes(
+    conn: &Connection,
+    folder_id: Option<&str>,
+    note_ids: &[String],
+) -> Result<(), String> {
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|err| err.to_string())?;
+    for (index, note_id) in note_ids.iter().enumerate() {
+        tx.execute(
+            "UPDATE notes SET sort_order = ?1 WHERE id = ?2 AND folder_id IS ?3",
+            params![(index as i64) + 1, note_id, folder_id],
+        )
+        .map_err(|err| err.to_string())?;
+    }
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+pub fn get_note(conn: &Connection, id: &str) -> Result<Note, String> {
+    let mut stmt = conn
+    .prepare("SELECT id, title, body_markdown, tags, folder_id, task_id, color, icon, sort_order, created_at,
+       updated_at FROM notes WHERE id = ?1")
+    .map_err(|err| err.to_string())?;
+    stmt.query_row(params![id], map_note_row)
+        .map_err(|err| err.to_string())
+}
+
+/// Plaintext snippet of a note's body for list views.
+pub fn note_preview(conn: &Connection, id: &str, max_chars: usize) -> Result<String, String> {
+    if !(1..=2000).contains(&max_chars) {
+        return Err("Max characters must be between 1 and 2000".to_string());
+    }
+    let note = get_note(conn, id)?;
+    Ok(preview_text(&note.body_markdown, max_chars))
+}
+
+pub fn note_stats(conn: &Connection, id: &str) -> Result<NoteStats, String> {
+    let note = get_note(conn, id)?;
+    let plain = strip_markdown(&note.body_markdown);
+    let word_count = plain.split_whitespace().count();
+    let reading_minutes = if word_count == 0 {
+        0
+    } else {
+        (word_count / 200).max(1)
+    };
+    Ok(NoteStats {
+        word_count,
+        character_count: plain.chars().count(),
+        reading_minutes,
+    })
+}
+
+pub fn create_note(conn: &Connection, input: NoteInput) -> Result<Note, String> {
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    let title = if input.title.trim().is_empty() {
+        "Untitled note".to_string()
+    } else {
+        input.title.trim().to_string()
+    };
+    let tags_csv = normalize_tags(&input.tags);
+    let (color, icon) = normalize_note_style(input.color.as_deref(), input.icon.as_deref())?;
+    let folder_id = normalize_folder_id(conn, input.folder_id.as_deref())?;
+    let task_id = normalize_note_task_id(conn, input.task_id.as_deref())?;
+
+    conn
+    .execute(
+      "INSERT INTO notes (id, title, body_markdown, tags, folder_id, task_id, color, icon, sort_order, created_at,
+       updated_at)
+       VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8,
+       (SELECT COALESCE(MAX(sort_order), 0) + 1 FROM notes WHERE folder_id IS ?5), ?9, ?10)",
+      params![id, title, input.body_markdown, tags_csv, folder_id, task_id, color, icon, now, now],
+    )
+    .map_err(|err| err.to_string())?;
+    register_note_tags(conn, &input.tags)?;
+    sync_note_links(conn, &id, &input.body_markdown)?;
+    get_note(conn, &id)
+}
+
+pub fn update_note(
+    conn: &Connection,
+    id: &str,
+    input: NoteInput,
+    expected_updated_at: Option<&str>,
+) -> Result<Note, String> {
+    let title = if input.title.trim().is_empty() {
+        "Untitled note".to_string()
+    } else {
+        input.title.trim().to_string()
+    };
+    let tags_csv = normalize_tags(&input.tags);
+    let (color, icon) = normalize_note_style(input.color.as_deref(), input.icon.as_deref())?;
+    let folder_id = normalize_folder_id(conn, input.folder_id.as_deref())?;
+    let task_id = normalize_note_task_id(conn, input.task_id.as_deref())?;
+    let now = Utc::now().to_rfc3339();
+
+    let changed = conn
+        .execute(
+            "UPDATE notes SET title = ?1, body_markdown = ?2, tags = ?3,
+       sort_order = CASE WHEN folder_id IS ?4 THEN sort_order
+         ELSE (SELECT COALESCE(MAX(sort_order), 0) + 1 FROM notes WHERE folder_id IS ?4) END,
+       folder_id = ?4, task_id = ?5, color = ?6, icon = ?7, updated_at = ?8
+       WHERE id = ?9 AND (?10 IS NULL OR updated_at = ?10)",
+            params![
+                title,
+                input.body_markdown,
+                tags_csv,
+                folder_id,
+                task_id,
+                color,
+                icon,
+                now,
+                id,
+                expected_updated_at
+            ],
+        )
+        .map_err(|err| err.to_string())?;
+    ensure_row_updated(changed, expected_updated_at)?;
+    register_note_tags(conn, &input.tags)?;
+    sync_note_links(conn, id, &input.body_markdown)?;
+    get_note(conn, id)
+}
+
+/// Moves a note into `folder_id` (or out of any folder when `None`), appending it to the
+/// end of the target folder's order.
+pub fn move_note(
+    conn: &Connection,
+    note_id: &str,
+    folder_id: Option<&str>,
+) -> Result<Note, String> {
+    let note = get_note(conn, note_id)?;
+    let folder_id = normalize_folder_id(conn, folder_id)?;
+    if note.folder_id == folder_id {
+        return Ok(note);
+    }
+    conn.execute(
+        "UPDATE notes SET folder_id = ?1,
+       sort_order = (SELECT COALESCE(MAX(sort_order), 0) + 1 FROM notes WHERE folder_id IS ?1),
+       updated_at = ?2
+       WHERE id = ?3",
+        params![folder_id, Utc::now().to_rfc3339(), note_id],
+    )
+    .map_err(|err| err.to_string())?;
+    get_note(conn, note_id)
+}
+
+pub fn list_note_folders(conn: &Connection) -> Result<Vec<NoteFolder>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, created_at, updated_at FROM note_folders ORDER BY lower(name) ASC",
+        )
+        .map_err(|err| err.to_string())?;
+    let folders = stmt
+        .query_map([], map_note_folder_row)
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    Ok(folders)
+}
+
+pub fn create_note_folder(conn: &Connection, name: &str) -> Result<NoteFolder, String> {
+    let cleaned = name.trim();
+    if cleaned.is_empty() {
+        return Err("Folder name cannot be empty".to_string());
+    }
+    let exists: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM note_folders WHERE LOWER(name) = LOWER(?1))",
+            params![cleaned],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+    if exists {
+        return Err(format!("A folder named \"{}\" already exists", cleaned));
+    }
+
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO note_folders (id, name, created_at, updated_at) VALUES (?1, ?2, ?3, ?4)",
+        params![id, cleaned, now, now],
+    )
+    .map_err(|err| err.to_string())?;
+
+    let mut stmt = conn
+        .prepare("SELECT id, name, created_at, updated_at FROM note_folders WHERE id = ?1")
+        .map_err(|err| err.to_string())?;
+    stmt.query_row(params![id], map_note_folder_row)
+        .map_err(|err| err.to_string())
+}
+
+pub fn delete_note_folder(conn: &Connection, folder_id: &str) -> Result<(), String> {
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|err| err.to_string())?;
+
+    tx.execute(
+        "UPDATE notes SET folder_id = NULL WHERE folder_id = ?1",
+        params![folder_id],
+    )
+    .map_err(|err| err.to_string())?;
+
+    let deleted = tx
+        .execute("DELETE FROM note_folders WHERE id = ?1", params![folder_id])
+        .map_err(|err| err.to_string())?;
+
+    if deleted == 0 {
+        return Err("Folder not found".to_string());
+    }
+
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+pub fn list_projects(conn: &Connection) -> Result<Vec<Project>, String> {
+    let mut stmt = conn
+        .prepare("SELECT * FROM projects ORDER BY lower(name) ASC")
+        .map_err(|err| err.to_string())?;
+    let projects = stmt
+        .query_map([], map_project_row)
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    Ok(projects)
+}
+
+pub fn get_project(conn: &Connection, id: &str) -> Result<Project, String> {
+    conn.query_row(
+        "SELECT * FROM projects WHERE id = ?1",
+        params![id],
+        map_project_row,
+    )
+    .optional()
+    .map_err(|err| err.to_string())?
+    .ok_or_else(|| "Project not found".to_string())
+}
+
+/// Trimmed name and `#rrggbb` color, rejecting a name another project already uses.
+fn normalize_project_input(
+    conn: &Connection,
+    input: &ProjectInput,
+    exclude_id: Option<&str>,
+) -> Result<(String, Option<String>), String> {
+    let name = input.name.trim();
+    if name.is_empty() {
+        return Err("Project name cannot be empty".to_string());
+    }
+    let exists: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM projects WHERE LOWER(name) = LOWER(?1)
+             AND (?2 IS NULL OR id != ?2))",
+            params![name, exclude_id],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+    if exists {
+        return Err(format!("A project named \"{}\" already exists", name));
+    }
+    let (color, _) = normalize_note_style(input.color.as_deref(), None)?;
+    Ok((name.to_string(), color))
+}
+
+pub fn create_project(conn: &Connection, input: ProjectInput) -> Result<Project, String> {
+    let (name, color) = normalize_project_input(conn, &input, None)?;
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO projects (id, name, color, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?4)",
+        params![id, name, color, now],
+    )
+    .map_err(|err| err.to_string())?;
+    get_project(conn, &id)
+}
+
+pub fn update_project(conn: &Connection, id: &str, input: ProjectInput) -> Result<Project, String> {
+    let (name, color) = normalize_project_input(conn, &input, Some(id))?;
+    let changed = conn
+        .execute(
+            "UPDATE projects SET name = ?1, color = ?2, updated_at = ?3 WHERE id = ?4",
+            params![name, color, Utc::now().to_rfc3339(), id],
+        )
+        .map_err(|err| err.to_string())?;
+    if changed == 0 {
+        return Err("Project not found".to_string());
+    }
+    get_project(conn, id)
+}
+
+/// Deletes the project; its tasks stay and simply lose their project.
+pub fn delete_project(conn: &Connection, id: &str) -> Result<(), String> {
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|err| err.to_string())?;
+
+    tx.execute(
+        "UPDATE tasks SET project_id = NULL WHERE project_id = ?1",
+        params![id],
+    )
+    .map_err(|err| err.to_string())?;
+
+    let deleted = tx
+        .execute("DELETE FROM projects WHERE id = ?1", params![id])
+        .map_err(|err| err.to_string())?;
+
+    if deleted == 0 {
+        return Err("Project not found".to_string());
+    }
+
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+/// Unarchived tasks in the project, in day order.
+pub fn list_tasks_by_project(conn: &Connection, project_id: &str) -> Result<Vec<Task>, String> {
+    get_project(conn, project_id)?;
+    list_by_query(
+        conn,
+        "SELECT * FROM tasks WHERE project_id = ?1 AND archived = 0
+         ORDER BY target_date ASC, sort_order ASC, created_at ASC",
+        project_id,
+    )
+}
+
+/// Puts the task in `project_id`, or takes it out of any project when blank.
+pub fn move_task_to_project(
+    conn: &Connection,
+    task_id: &str,
+    project_id: Option<&str>,
+) -> Result<Task, String> {
+    let project_id = normalize_project_id(conn, project_id)?;
+    let changed = conn
+        .execute(
+            "UPDATE tasks SET project_id = ?1, updated_at = ?2 WHERE id = ?3",
+            params![project_id, Utc::now().to_rfc3339(), task_id],
+        )
+        .map_err(|err| err.to_string())?;
+    if changed == 0 {
+        return Err("Task not found".to_string());
+    }
+    get_task(conn, task_id)
+}
+
+/// Blank means no project; anything else must name an existing project.
+fn normalize_project_id(
+    conn: &Connection,
+    project_id: Option<&str>,
+) -> Result<Option<String>, String> {
+    match project_id.map(str::trim).filter(|value| !value.is_empty()) {
+        Some(project_id) => get_project(conn, project_id).map(|project| Some(project.id)),
+        None => Ok(None),
+    }
+}
+
+fn map_note_template_row(row: &rusqlite::Row<'_>) -> Result<NoteTemplate, rusqlite::Error> {
+    let tags_csv: String = row.get("tags")?;
+    Ok(NoteTemplate {
+        id: row.get("id")?,
+        name: row.get("name")?,
+        body_markdown: row.get("body_markdown")?,
+        tags: parse_tags(&tags_csv),
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+fn get_note_template(conn: &Connection, id: &str) -> Result<NoteTemplate, String> {
+    conn.query_row(
+        "SELECT * FROM note_templates WHERE id = ?1",
+        params![id],
+        map_note_template_row,
+    )
+    .optional()
+    .map_err(|err| err.to_string())?
+    .ok_or_else(|| "Template not found".to_string())
 }
 
-pub fn update_note(conn: &Connection, id: &str, input: NoteInput) -> Result<Note, String> {
-    let title = if input.title.trim().is_empty() {
-        "Untitled note".to_string()
-    } else {
-        input.title.trim().to_string()
-    };
-    let tags_csv = normalize_tags(&input.tags);
+pub fn create_note_template(
+    conn: &Connection,
+    input: NoteTemplateInput,
+) -> Result<NoteTemplate, String> {
+    let name = input.name.trim();
+    if name.is_empty() {
+        return Err("Template name cannot be empty".to_string());
+    }
+    let id = Uuid::new_v4().to_string();
     let now = Utc::now().to_rfc3339();
-
-    conn
-    .execute(
-      "UPDATE notes SET title = ?1, body_markdown = ?2, tags = ?3, folder_id = ?4, updated_at = ?5 WHERE id = ?6",
-      params![title, input.body_markdown, tags_csv, input.folder_id, now, id],
+    conn.execute(
+        "INSERT INTO note_templates (id, name, body_markdown, tags, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            id,
+            name,
+            input.body_markdown,
+            normalize_tags(&input.tags),
+            now,
+            now
+        ],
     )
     .map_err(|err| err.to_string())?;
-    get_note(conn, id)
+    get_note_template(conn, &id)
 }
 
-pub fn list_note_folders(conn: &Connection) -> Result<Vec<NoteFolder>, String> {
+pub fn list_note_templates(conn: &Connection) -> Result<Vec<NoteTemplate>, String> {
     let mut stmt = conn
-        .prepare(
-            "SELECT id, name, created_at, updated_at FROM note_folders ORDER BY lower(name) ASC",
-        )
+        .prepare("SELECT * FROM note_templates ORDER BY lower(name) ASC, created_at ASC")
         .map_err(|err| err.to_string())?;
-    let folders = stmt
-        .query_map([], map_note_folder_row)
+    let templates = stmt
+        .query_map([], map_note_template_row)
         .map_err(|err| err.to_string())?
         .filter_map(Result::ok)
         .collect();
-    Ok(folders)
+    Ok(templates)
 }
 
-pub fn create_note_folder(conn: &Connection, name: &str) -> Result<NoteFolder, String> {
-    let cleaned = name.trim();
-    if cleaned.is_empty() {
-        return Err("Folder name cannot be empty".to_string());
+pub fn delete_note_template(conn: &Connection, id: &str) -> Result<(), String> {
+    let deleted = conn
+        .execute("DELETE FROM note_templates WHERE id = ?1", params![id])
+        .map_err(|err| err.to_string())?;
+    if deleted == 0 {
+        return Err("Template not found".to_string());
     }
+    Ok(())
+}
 
-    let id = Uuid::new_v4().to_string();
-    let now = Utc::now().to_rfc3339();
+/// New unfiled note with the template's body and tags; `{{date}}` in the body becomes
+/// `today_value`.
+pub fn create_note_from_template(
+    conn: &Connection,
+    template_id: &str,
+    title: &str,
+    today_value: &str,
+) -> Result<Note, String> {
+    let template = get_note_template(conn, template_id)?;
+    create_note(
+        conn,
+        NoteInput {
+            title: title.to_string(),
+            body_markdown: template.body_markdown.replace("{{date}}", today_value),
+            tags: template.tags,
+            folder_id: None,
+            task_id: None,
+            color: None,
+            icon: None,
+        },
+    )
+}
+
+pub fn delete_note(conn: &Connection, id: &str) -> Result<(), String> {
+    conn.execute("DELETE FROM notes WHERE id = ?1", params![id])
+        .map_err(|err| err.to_string())?;
+    resolve_note_links(conn)
+}
+
+/// Re-inserts a deleted note under its original id. Its attachments are not restored.
+pub fn restore_note(conn: &Connection, note: &Note) -> Result<Note, String> {
+    let folder_id = match note.folder_id.as_deref() {
+        Some(folder_id) if folder_exists(conn, folder_id)? => Some(folder_id),
+        _ => None,
+    };
+    let task_id = match note.task_id.as_deref() {
+        Some(task_id) if get_task(conn, task_id).is_ok() => Some(task_id),
+        _ => None,
+    };
     conn.execute(
-        "INSERT INTO note_folders (id, name, created_at, updated_at) VALUES (?1, ?2, ?3, ?4)",
-        params![id, cleaned, now, now],
+        "INSERT INTO notes (id, title, body_markdown, tags, folder_id, task_id, color, icon, sort_order,
+       created_at, updated_at)
+       VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            note.id,
+            note.title,
+            note.body_markdown,
+            normalize_tags(&note.tags),
+            folder_id,
+            task_id,
+            note.color,
+            note.icon,
+            note.sort_order,
+            note.created_at,
+            Utc::now().to_rfc3339()
+        ],
     )
     .map_err(|err| err.to_string())?;
+    sync_note_links(conn, &note.id, &note.body_markdown)?;
+    resolve_note_links(conn)?;
+    get_note(conn, &note.id)
+}
 
-    let mut stmt = conn
-        .prepare("SELECT id, name, created_at, updated_at FROM note_folders WHERE id = ?1")
-        .map_err(|err| err.to_string())?;
-    stmt.query_row(params![id], map_note_folder_row)
-        .map_err(|err| err.to_string())
+/// Blank means unfiled; anything else must name an existing folder.
+fn normalize_folder_id(
+    conn: &Connection,
+    folder_id: Option<&str>,
+) -> Result<Option<String>, String> {
+    match folder_id.map(str::trim).filter(|value| !value.is_empty()) {
+        Some(folder_id) if !folder_exists(conn, folder_id)? => Err("Folder not found".to_string()),
+        folder_id => Ok(folder_id.map(str::to_string)),
+    }
 }
 
-pub fn delete_note_folder(conn: &Connection, folder_id: &str) -> Result<(), String> {
-    let tx = conn
-        .unchecked_transaction()
-        .map_err(|err| err.to_string())?;
+/// Blank means no linked task; anything else must name an existing task.
+fn normalize_note_task_id(
+    conn: &Connection,
+    task_id: Option<&str>,
+) -> Result<Option<String>, String> {
+    match task_id.map(str::trim).filter(|value| !value.is_empty()) {
+        Some(task_id) if !task_exists(conn, task_id)? => Err("Task not found".to_string()),
+        task_id => Ok(task_id.map(str::to_string)),
+    }
+}
 
-    tx.execute(
-        "UPDATE notes SET folder_id = NULL WHERE folder_id = ?1",
+fn task_exists(conn: &Connection, task_id: &str) -> Result<bool, String> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM tasks WHERE id = ?1)",
+        params![task_id],
+        |row| row.get::<_, i32>(0),
+    )
+    .map(|exists| exists == 1)
+    .map_err(|err| err.to_string())
+}
+
+fn folder_exists(conn: &Connection, folder_id: &str) -> Result<bool, String> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM note_folders WHERE id = ?1)",
         params![folder_id],
+        |row| row.get::<_, i32>(0),
     )
-    .map_err(|err| err.to_string())?;
+    .map(|exists| exists == 1)
+    .map_err(|err| err.to_string())
+}
 
-    let deleted = tx
-        .execute("DELETE FROM note_folders WHERE id = ?1", params![folder_id])
+pub fn get_note_attachment(conn: &Connection, id: &str) -> Result<NoteAttachment, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, note_id, filename, path_relative, thumb_path_relative, created_at
+       FROM note_attachments
+       WHERE id = ?1",
+        )
         .map_err(|err| err.to_string())?;
+    stmt.query_row(params![id], map_note_attachment_row)
+        .optional()
+        .map_err(|err| err.to_string())?
+        .ok_or_else(|| "Attachment not found".to_string())
+}
 
-    if deleted == 0 {
-        return Err("Folder not found".to_string());
-    }
-
-    tx.commit().map_err(|err| err.to_string())?;
-    Ok(())
+pub fn list_all_note_attachments(conn: &Connection) -> Result<Vec<NoteAttachment>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, note_id, filename, path_relative, thumb_path_relative, created_at
+       FROM note_attachments
+       ORDER BY created_at ASC",
+        )
+        .map_err(|err| err.to_string())?;
+    let attachments = stmt
+        .query_map([], map_note_attachment_row)
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    Ok(attachments)
 }
 
-pub fn delete_note(conn: &Connection, id: &str) -> Result<(), String> {
-    conn.execute("DELETE FROM notes WHERE id = ?1", params![id])
+pub fn delete_note_attachments(conn: &Connection, ids: &[String]) -> Result<(), String> {
+    let tx = conn
+        .unchecked_transaction()
         .map_err(|err| err.to_string())?;
-    Ok(())
+    for id in ids {
+        tx.execute("DELETE FROM note_attachments WHERE id = ?1", params![id])
+            .map_err(|err| err.to_string())?;
+    }
+    tx.commit().map_err(|err| err.to_string())
 }
 
 pub fn list_note_attachments(
@@ -1064,7 +4179,7 @@ pub fn list_note_attachments(
 ) -> Result<Vec<NoteAttachment>, String> {
     let mut stmt = conn
         .prepare(
-            "SELECT id, note_id, filename, path_relative, created_at
+            "SELECT id, note_id, filename, path_relative, thumb_path_relative, created_at
        FROM note_attachments
        WHERE note_id = ?1
        ORDER BY created_at DESC",
@@ -1083,18 +4198,19 @@ pub fn create_note_attachment(
     note_id: &str,
     filename: &str,
     path_relative: &str,
+    thumb_path_relative: Option<&str>,
 ) -> Result<NoteAttachment, String> {
     let id = Uuid::new_v4().to_string();
     let now = Utc::now().to_rfc3339();
     conn.execute(
-        "INSERT INTO note_attachments (id, note_id, filename, path_relative, created_at)
-       VALUES (?1, ?2, ?3, ?4, ?5)",
-        params![id, note_id, filename, path_relative, now],
+        "INSERT INTO note_attachments (id, note_id, filename, path_relative, thumb_path_relative, created_at)
+       VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![id, note_id, filename, path_relative, thumb_path_relative, now],
     )
     .map_err(|err| err.to_string())?;
 
     let mut stmt = conn
-    .prepare("SELECT id, note_id, filename, path_relative, created_at FROM note_attachments WHERE id = ?1")
+    .prepare("SELECT id, note_id, filename, path_relative, thumb_path_relative, created_at FROM note_attachments WHERE id = ?1")
     .map_err(|err| err.to_string())?;
     stmt.query_row(params![id], map_note_attachment_row)
         .map_err(|err| err.to_string())
@@ -1103,7 +4219,7 @@ pub fn create_note_attachment(
 pub fn list_checkin_people(conn: &Connection) -> Result<Vec<CheckinPerson>, String> {
     let mut stmt = conn
         .prepare(
-            "SELECT id, name, relationship, created_at, updated_at
+            "SELECT id, name, relationship, cadence_interval, cadence_unit, created_at, updated_at
        FROM checkin_people
        ORDER BY lower(name) ASC, created_at ASC",
         )
@@ -1125,21 +4241,135 @@ pub fn create_checkin_person(
         return Err("Name is required".to_string());
     }
     let relationship = normalize_relationship(&input.relationship)?;
+    let (cadence_interval, cadence_unit) =
+        normalize_cadence(input.cadence_interval, input.cadence_unit.as_deref())?;
     let id = Uuid::new_v4().to_string();
     let now = Utc::now().to_rfc3339();
 
     conn.execute(
-        "INSERT INTO checkin_people (id, name, relationship, created_at, updated_at)
-       VALUES (?1, ?2, ?3, ?4, ?5)",
-        params![id, name, relationship, now, now],
+        "INSERT INTO checkin_people (id, name, relationship, cadence_interval, cadence_unit, created_at, updated_at)
+       VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![id, name, relationship, cadence_interval, cadence_unit, now, now],
     )
     .map_err(|err| err.to_string())?;
 
+    get_checkin_person(conn, &id)
+}
+
+/// Renames a person and/or changes their relationship, keeping their check-in history.
+/// Cadence is left alone; `set_checkin_cadence` owns it.
+pub fn update_checkin_person(
+    conn: &Connection,
+    id: &str,
+    input: CheckinPersonInput,
+) -> Result<CheckinPerson, String> {
+    let name = input.name.trim();
+    if name.is_empty() {
+        return Err("Name is required".to_string());
+    }
+    let relationship = normalize_relationship(&input.relationship)?;
+    let updated = conn
+        .execute(
+            "UPDATE checkin_people SET name = ?1, relationship = ?2, updated_at = ?3 WHERE id = ?4",
+            params![name, relationship, Utc::now().to_rfc3339(), id],
+        )
+        .map_err(|err| err.to_string())?;
+    if updated == 0 {
+        return Err("Person not found".to_string());
+    }
+    get_checkin_person(conn, id)
+}
+
+pub fn get_checkin_person(conn: &Connection, id: &str) -> Result<CheckinPerson, String> {
     let mut stmt = conn
-    .prepare("SELECT id, name, relationship, created_at, updated_at FROM checkin_people WHERE id = ?1")
-    .map_err(|err| err.to_string())?;
+        .prepare(
+            "SELECT id, name, relationship, cadence_interval, cadence_unit, created_at, updated_at
+       FROM checkin_people
+       WHERE id = ?1",
+        )
+        .map_err(|err| err.to_string())?;
     stmt.query_row(params![id], map_checkin_person_row)
-        .map_err(|err| err.to_string())
+        .optional()
+        .map_err(|err| err.to_string())?
+        .ok_or_else(|| "Person not found".to_string())
+}
+
+pub fn set_checkin_cadence(
+    conn: &Connection,
+    person_id: &str,
+    interval: Option<i32>,
+    unit: Option<&str>,
+) -> Result<CheckinPerson, String> {
+    let (cadence_interval, cadence_unit) = normalize_cadence(interval, unit)?;
+    let updated = conn
+        .execute(
+            "UPDATE checkin_people SET cadence_interval = ?1, cadence_unit = ?2, updated_at = ?3
+       WHERE id = ?4",
+            params![
+                cadence_interval,
+                cadence_unit,
+                Utc::now().to_rfc3339(),
+                person_id
+            ],
+        )
+        .map_err(|err| err.to_string())?;
+    if updated == 0 {
+        return Err("Person not found".to_string());
+    }
+    get_checkin_person(conn, person_id)
+}
+
+pub fn find_duplicate_people(conn: &Connection) -> Result<Vec<DuplicatePeople>, String> {
+    let mut groups: BTreeMap<String, Vec<CheckinPerson>> = BTreeMap::new();
+    for person in list_checkin_people(conn)? {
+        groups
+            .entry(person.name.trim().to_lowercase())
+            .or_default()
+            .push(person);
+    }
+    Ok(groups
+        .into_values()
+        .filter(|people| people.len() > 1)
+        .map(|people| DuplicatePeople {
+            name: people[0].name.clone(),
+            people,
+        })
+        .collect())
+}
+
+pub fn merge_checkin_people(
+    conn: &Connection,
+    keep_id: &str,
+    merge_ids: &[String],
+) -> Result<usize, String> {
+    if merge_ids.is_empty() {
+        return Err("Select at least one person to merge".to_string());
+    }
+    if merge_ids.iter().any(|id| id == keep_id) {
+        return Err("Cannot merge a person into themselves".to_string());
+    }
+    let _ = get_checkin_person(conn, keep_id)?;
+    for id in merge_ids {
+        let _ = get_checkin_person(conn, id)?;
+    }
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|err| err.to_string())?;
+    let now = Utc::now().to_rfc3339();
+    let mut reassigned = 0;
+    for id in merge_ids {
+        reassigned += tx
+            .execute(
+                "UPDATE checkins SET person_id = ?1, updated_at = ?2 WHERE person_id = ?3",
+                params![keep_id, now, id],
+            )
+            .map_err(|err| err.to_string())?;
+        tx.execute("DELETE FROM checkin_people WHERE id = ?1", params![id])
+            .map_err(|err| err.to_string())?;
+    }
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(reassigned)
 }
 
 pub fn delete_checkin_person(conn: &Connection, id: &str) -> Result<(), String> {
@@ -1183,6 +4413,213 @@ pub fn list_checkins(conn: &Connection, person_id: Option<&str>) -> Result<Vec<C
     Ok(checkins)
 }
 
+/// People without a cadence are listed but never overdue; people with a cadence and no
+/// check-ins yet count as overdue.
+pub fn checkin_cadence_report(
+    conn: &Connection,
+    today_value: &str,
+) -> Result<Vec<CadenceHealth>, String> {
+    let today_date = parse_date(today_value)?;
+    let mut report = Vec::new();
+    for person in list_checkin_people(conn)? {
+        let last_checkin_date: Option<String> = conn
+            .query_row(
+                "SELECT MAX(checkin_date) FROM checkins WHERE person_id = ?1",
+                params![person.id],
+                |row| row.get(0),
+            )
+            .map_err(|err| err.to_string())?;
+        let (days_since, due_date, overdue) = match last_checkin_date.as_deref() {
+            Some(last) => {
+                let days_since = (today_date - parse_date(last)?).num_days();
+                let due_date = advance_by_cadence(&person, last)?;
+                let overdue = due_date.as_deref().is_some_and(|due| due < today_value);
+                (Some(days_since), due_date, overdue)
+            }
+            None => (None, None, person.cadence_interval.is_some()),
+        };
+        report.push(CadenceHealth {
+            person,
+            last_checkin_date,
+            days_since,
+            due_date,
+            overdue,
+        });
+    }
+    report.sort_by(|a, b| {
+        b.overdue.cmp(&a.overdue).then_with(|| {
+            b.days_since
+                .unwrap_or(i64::MAX)
+                .cmp(&a.days_since.unwrap_or(i64::MAX))
+        })
+    });
+    Ok(report)
+}
+
+/// People whose latest check-in is more than `days` before `today_value`, or who have none.
+/// Never-met people come first, then the longest-neglected.
+pub fn people_needing_checkin(
+    conn: &Connection,
+    today_value: &str,
+    days: i64,
+) -> Result<Vec<NeglectedPerson>, String> {
+    if !(1..=3650).contains(&days) {
+        return Err("Days must be between 1 and 3650".to_string());
+    }
+    let today_date = parse_date(today_value)?;
+    let cutoff = (today_date - Duration::days(days))
+        .format("%Y-%m-%d")
+        .to_string();
+    let mut stmt = conn
+        .prepare(
+            "SELECT p.id, p.name, p.relationship, p.cadence_interval, p.cadence_unit, p.created_at,
+              p.updated_at, last.checkin_date AS last_checkin_date
+       FROM checkin_people p
+       LEFT JOIN (SELECT person_id, MAX(checkin_date) AS checkin_date FROM checkins GROUP BY person_id) last
+         ON last.person_id = p.id
+       WHERE last.checkin_date IS NULL OR last.checkin_date < ?1
+       ORDER BY last.checkin_date IS NOT NULL, last.checkin_date ASC, lower(p.name) ASC",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows: Vec<(CheckinPerson, Option<String>)> = stmt
+        .query_map(params![cutoff], |row| {
+            Ok((map_checkin_person_row(row)?, row.get("last_checkin_date")?))
+        })
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    rows.into_iter()
+        .map(|(person, last_checkin_date)| {
+            let days_since = match last_checkin_date.as_deref() {
+                Some(last) => Some((today_date - parse_date(last)?).num_days()),
+                None => None,
+            };
+            Ok(NeglectedPerson {
+                person,
+                last_checkin_date,
+                days_since,
+            })
+        })
+        .collect()
+}
+
+pub fn checkin_person_summary(
+    conn: &Connection,
+    person_id: &str,
+    today_value: &str,
+) -> Result<CheckinPersonSummary, String> {
+    let person_exists = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM checkin_people WHERE id = ?1)",
+            params![person_id],
+            |row| row.get::<_, i32>(0),
+        )
+        .map_err(|err| err.to_string())?;
+    if person_exists == 0 {
+        return Err("Person not found".to_string());
+    }
+
+    let (total_checkins, last_checkin_date): (i64, Option<String>) = conn
+        .query_row(
+            "SELECT COUNT(*), MAX(checkin_date) FROM checkins WHERE person_id = ?1",
+            params![person_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|err| err.to_string())?;
+
+    let next_checkin_date: Option<String> = conn
+        .query_row(
+            "SELECT MIN(next_checkin_date) FROM checkins
+       WHERE person_id = ?1 AND next_checkin_date IS NOT NULL AND next_checkin_date >= ?2",
+            params![person_id, today_value],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+
+    // Only the latest check-in's action items are outstanding; earlier lists are superseded.
+    let latest_action_items: Option<String> = conn
+        .query_row(
+            "SELECT action_items FROM checkins
+       WHERE person_id = ?1
+       ORDER BY checkin_date DESC, updated_at DESC
+       LIMIT 1",
+            params![person_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?
+        .flatten();
+
+    Ok(CheckinPersonSummary {
+        person_id: person_id.to_string(),
+        total_checkins,
+        last_checkin_date,
+        next_checkin_date,
+        outstanding_action_items: latest_action_items
+            .as_deref()
+            .map(count_open_action_items)
+            .unwrap_or(0),
+    })
+}
+
+pub fn export_checkins_markdown(conn: &Connection, person_id: &str) -> Result<String, String> {
+    let person = list_checkin_people(conn)?
+        .into_iter()
+        .find(|person| person.id == person_id)
+        .ok_or_else(|| "Person not found".to_string())?;
+
+    let mut out = format!("# {}\n\n_{}_\n", person.name, person.relationship);
+    for checkin in list_checkins(conn, Some(person_id))? {
+        out.push_str(&format!("\n## {}\n", checkin.checkin_date));
+        for (heading, body) in [
+            ("Discussion", &checkin.discussion),
+            ("Notes", &checkin.notes),
+            ("Action items", &checkin.action_items),
+        ] {
+            if let Some(body) = body
+                .as_deref()
+                .map(str::trim)
+                .filter(|body| !body.is_empty())
+            {
+                out.push_str(&format!("\n### {}\n\n{}\n", heading, body));
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Creates an empty check-in dated to the person's next scheduled meeting.
+pub fn create_next_checkin(
+    conn: &Connection,
+    person_id: &str,
+    today_value: &str,
+) -> Result<Checkin, String> {
+    let person = get_checkin_person(conn, person_id)?;
+    let latest = list_checkins(conn, Some(person_id))?.into_iter().next();
+    let checkin_date = match latest {
+        Some(latest) => match latest.next_checkin_date {
+            Some(next_date) => next_date,
+            None => advance_by_cadence(&person, &latest.checkin_date)?
+                .unwrap_or_else(|| today_value.to_string()),
+        },
+        None => today_value.to_string(),
+    };
+
+    create_checkin(
+        conn,
+        CheckinInput {
+            person_id: person.id,
+            checkin_date,
+            discussion: None,
+            notes: None,
+            action_items: None,
+            next_checkin_date: None,
+            reminder_enabled: false,
+            reminder_time: None,
+        },
+    )
+}
+
 pub fn get_checkin(conn: &Connection, id: &str) -> Result<Checkin, String> {
     let mut stmt = conn
     .prepare(
@@ -1199,13 +4636,14 @@ pub fn get_checkin(conn: &Connection, id: &str) -> Result<Checkin, String> {
 pub fn create_checkin(conn: &Connection, input: CheckinInput) -> Result<Checkin, String> {
     let person_id = input.person_id.trim().to_string();
     let checkin_date = input.checkin_date.trim().to_string();
-    let _ = parse_date(&checkin_date)?;
-    if let Some(next_date) = input.next_checkin_date.as_deref() {
-        let _ = parse_date(next_date.trim())?;
-    }
-    if let Some(reminder_time) = input.reminder_time.as_deref() {
-        let _ = parse_time(reminder_time.trim())?;
-    }
+    let next_checkin_date =
+        default_next_checkin_date(conn, &person_id, &checkin_date, input.next_checkin_date)?;
+    validate_checkin_schedule(
+        &checkin_date,
+        next_checkin_date.as_deref(),
+        input.reminder_enabled,
+        input.reminder_time.as_deref(),
+    )?;
 
     let person_exists = conn
         .query_row(
@@ -1221,7 +4659,6 @@ pub fn create_checkin(conn: &Connection, input: CheckinInput) -> Result<Checkin,
     let discussion = normalize_optional_text(input.discussion);
     let notes = normalize_optional_text(input.notes);
     let action_items = normalize_optional_text(input.action_items);
-    let next_checkin_date = normalize_optional_text(input.next_checkin_date);
     let reminder_time = normalize_optional_text(input.reminder_time);
     let reminder_enabled = if input.reminder_enabled { 1 } else { 0 };
     let reminder_state = normalize_reminder_state(
@@ -1258,16 +4695,22 @@ pub fn create_checkin(conn: &Connection, input: CheckinInput) -> Result<Checkin,
     get_checkin(conn, &id)
 }
 
-pub fn update_checkin(conn: &Connection, id: &str, input: CheckinInput) -> Result<Checkin, String> {
+pub fn update_checkin(
+    conn: &Connection,
+    id: &str,
+    input: CheckinInput,
+    expected_updated_at: Option<&str>,
+) -> Result<Checkin, String> {
     let person_id = input.person_id.trim().to_string();
     let checkin_date = input.checkin_date.trim().to_string();
-    let _ = parse_date(&checkin_date)?;
-    if let Some(next_date) = input.next_checkin_date.as_deref() {
-        let _ = parse_date(next_date.trim())?;
-    }
-    if let Some(reminder_time) = input.reminder_time.as_deref() {
-        let _ = parse_time(reminder_time.trim())?;
-    }
+    // Unlike create, no cadence default here so a next date can be cleared.
+    let next_checkin_date = normalize_optional_text(input.next_checkin_date);
+    validate_checkin_schedule(
+        &checkin_date,
+        next_checkin_date.as_deref(),
+        input.reminder_enabled,
+        input.reminder_time.as_deref(),
+    )?;
 
     let person_exists = conn
         .query_row(
@@ -1283,7 +4726,6 @@ pub fn update_checkin(conn: &Connection, id: &str, input: CheckinInput) -> Resul
     let discussion = normalize_optional_text(input.discussion);
     let notes = normalize_optional_text(input.notes);
     let action_items = normalize_optional_text(input.action_items);
-    let next_checkin_date = normalize_optional_text(input.next_checkin_date);
     let reminder_time = normalize_optional_text(input.reminder_time);
     let reminder_enabled = if input.reminder_enabled { 1 } else { 0 };
     let reminder_state = normalize_reminder_state(
@@ -1292,8 +4734,9 @@ pub fn update_checkin(conn: &Connection, id: &str, input: CheckinInput) -> Resul
         reminder_time.as_deref(),
     );
 
-    conn.execute(
-        "UPDATE checkins
+    let changed = conn
+        .execute(
+            "UPDATE checkins
        SET person_id = ?1,
            checkin_date = ?2,
            discussion = ?3,
@@ -1304,24 +4747,119 @@ pub fn update_checkin(conn: &Connection, id: &str, input: CheckinInput) -> Resul
            reminder_time = ?8,
            reminder_state = ?9,
            updated_at = ?10
-       WHERE id = ?11",
+       WHERE id = ?11 AND (?12 IS NULL OR updated_at = ?12)",
+            params![
+                person_id,
+                checkin_date,
+                discussion,
+                notes,
+                action_items,
+                next_checkin_date,
+                reminder_enabled,
+                reminder_time,
+                reminder_state,
+                Utc::now().to_rfc3339(),
+                id,
+                expected_updated_at
+            ],
+        )
+        .map_err(|err| err.to_string())?;
+    ensure_row_updated(changed, expected_updated_at)?;
+
+    get_checkin(conn, id)
+}
+
+pub fn list_checkin_attachments(
+    conn: &Connection,
+    checkin_id: &str,
+) -> Result<Vec<CheckinAttachment>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, checkin_id, filename, path_relative, thumb_path_relative, created_at
+       FROM checkin_attachments
+       WHERE checkin_id = ?1
+       ORDER BY created_at DESC",
+        )
+        .map_err(|err| err.to_string())?;
+    let attachments = stmt
+        .query_map(params![checkin_id], map_checkin_attachment_row)
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    Ok(attachments)
+}
+
+pub fn get_checkin_attachment(conn: &Connection, id: &str) -> Result<CheckinAttachment, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, checkin_id, filename, path_relative, thumb_path_relative, created_at
+       FROM checkin_attachments
+       WHERE id = ?1",
+        )
+        .map_err(|err| err.to_string())?;
+    stmt.query_row(params![id], map_checkin_attachment_row)
+        .optional()
+        .map_err(|err| err.to_string())?
+        .ok_or_else(|| "Attachment not found".to_string())
+}
+
+pub fn create_checkin_attachment(
+    conn: &Connection,
+    checkin_id: &str,
+    filename: &str,
+    path_relative: &str,
+    thumb_path_relative: Option<&str>,
+) -> Result<CheckinAttachment, String> {
+    let id = Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO checkin_attachments (id, checkin_id, filename, path_relative, thumb_path_relative, created_at)
+       VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
         params![
-            person_id,
-            checkin_date,
-            discussion,
-            notes,
-            action_items,
-            next_checkin_date,
-            reminder_enabled,
-            reminder_time,
-            reminder_state,
-            Utc::now().to_rfc3339(),
-            id
+            id,
+            checkin_id,
+            filename,
+            path_relative,
+            thumb_path_relative,
+            Utc::now().to_rfc3339()
         ],
     )
     .map_err(|err| err.to_string())?;
+    get_checkin_attachment(conn, &id)
+}
 
-    get_checkin(conn, id)
+pub fn delete_checkin_attachment(conn: &Connection, id: &str) -> Result<(), String> {
+    conn.execute("DELETE FROM checkin_attachments WHERE id = ?1", params![id])
+        .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+/// Re-inserts a deleted check-in under its original id; fails if the person is gone.
+/// Its attachments are not restored.
+pub fn restore_checkin(conn: &Connection, checkin: &Checkin) -> Result<Checkin, String> {
+    get_checkin_person(conn, &checkin.person_id)?;
+    conn.execute(
+        "INSERT INTO checkins (
+        id, person_id, checkin_date, discussion, notes, action_items, next_checkin_date,
+        reminder_enabled, reminder_time, reminder_state, created_at, updated_at
+      )
+      VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        params![
+            checkin.id,
+            checkin.person_id,
+            checkin.checkin_date,
+            checkin.discussion,
+            checkin.notes,
+            checkin.action_items,
+            checkin.next_checkin_date,
+            checkin.reminder_enabled,
+            checkin.reminder_time,
+            checkin.reminder_state,
+            checkin.created_at,
+            Utc::now().to_rfc3339()
+        ],
+    )
+    .map_err(|err| err.to_string())?;
+    get_checkin(conn, &checkin.id)
 }
 
 pub fn delete_checkin(conn: &Connection, id: &str) -> Result<(), String> {
@@ -1342,7 +4880,7 @@ pub fn list_due_checkin_reminders(
        WHERE c.reminder_enabled = 1
          AND c.next_checkin_date IS NOT NULL
          AND c.reminder_time IS NOT NULL
-         AND (c.reminder_state IS NULL OR c.reminder_state != 'sent')",
+         AND (c.reminder_state IS NULL OR c.reminder_state NOT IN ('sent', 'acknowledged'))",
         )
         .map_err(|err| err.to_string())?;
 
@@ -1371,6 +4909,210 @@ pub fn list_due_checkin_reminders(
     Ok(due)
 }
 
+/// Check-ins whose next meeting falls on `date`, timed ones first by reminder time,
+/// then by person name.
+pub fn checkins_scheduled_for(
+    conn: &Connection,
+    date: &str,
+) -> Result<Vec<CheckinWithPerson>, String> {
+    let date = date.trim();
+    let _ = parse_date(date)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT c.id, c.person_id, c.checkin_date, c.discussion, c.notes, c.action_items,
+              c.next_checkin_date, c.reminder_enabled, c.reminder_time, c.reminder_state,
+              c.created_at, c.updated_at, p.name AS person_name
+       FROM checkins c
+       INNER JOIN checkin_people p ON p.id = c.person_id
+       WHERE c.next_checkin_date = ?1
+       ORDER BY c.reminder_time IS NULL, c.reminder_time ASC, lower(p.name) ASC",
+        )
+        .map_err(|err| err.to_string())?;
+    let scheduled = stmt
+        .query_map(params![date], |row| {
+            Ok(CheckinWithPerson {
+                checkin: map_checkin_row(row)?,
+                person_name: row.get("person_name")?,
+            })
+        })
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    Ok(scheduled)
+}
+
+const CHECKIN_SEARCH_LIMIT: i64 = 50;
+
+/// Check-ins whose discussion, notes or action items contain `query` (case-insensitive),
+/// newest first.
+pub fn search_checkins(conn: &Connection, query: &str) -> Result<Vec<CheckinWithPerson>, String> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let pattern = format!(
+        "%{}%",
+        query
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    );
+    let mut stmt = conn
+        .prepare(
+            "SELECT c.id, c.person_id, c.checkin_date, c.discussion, c.notes, c.action_items,
+              c.next_checkin_date, c.reminder_enabled, c.reminder_time, c.reminder_state,
+              c.created_at, c.updated_at, p.name AS person_name
+       FROM checkins c
+       INNER JOIN checkin_people p ON p.id = c.person_id
+       WHERE c.discussion LIKE ?1 ESCAPE '\\' OR c.notes LIKE ?1 ESCAPE '\\'
+         OR c.action_items LIKE ?1 ESCAPE '\\'
+       ORDER BY c.checkin_date DESC, c.created_at DESC
+       LIMIT ?2",
+        )
+        .map_err(|err| err.to_string())?;
+    let matches = stmt
+        .query_map(params![pattern, CHECKIN_SEARCH_LIMIT], |row| {
+            Ok(CheckinWithPerson {
+                checkin: map_checkin_row(row)?,
+                person_name: row.get("person_name")?,
+            })
+        })
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    Ok(matches)
+}
+
+/// Each person's most recent check-in when it has no next check-in date, newest first.
+/// Two check-ins on the same latest day count as one: the one created last.
+pub fn checkins_without_next(conn: &Connection) -> Result<Vec<CheckinWithPerson>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT c.id, c.person_id, c.checkin_date, c.discussion, c.notes, c.action_items,
+              c.next_checkin_date, c.reminder_enabled, c.reminder_time, c.reminder_state,
+              c.created_at, c.updated_at, p.name AS person_name
+       FROM checkins c
+       INNER JOIN (SELECT person_id, MAX(checkin_date) AS checkin_date FROM checkins GROUP BY person_id) latest
+         ON latest.person_id = c.person_id AND latest.checkin_date = c.checkin_date
+       INNER JOIN checkin_people p ON p.id = c.person_id
+       ORDER BY c.checkin_date DESC, c.created_at DESC",
+        )
+        .map_err(|err| err.to_string())?;
+    let latest: Vec<CheckinWithPerson> = stmt
+        .query_map([], |row| {
+            Ok(CheckinWithPerson {
+                checkin: map_checkin_row(row)?,
+                person_name: row.get("person_name")?,
+            })
+        })
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    let mut seen = HashSet::new();
+    Ok(latest
+        .into_iter()
+        .filter(|entry| seen.insert(entry.checkin.person_id.clone()))
+        .filter(|entry| entry.checkin.next_checkin_date.is_none())
+        .collect())
+}
+
+/// Soonest scheduled check-in that has not passed yet, across everyone. A date without a
+/// reminder time counts as upcoming for that whole day and sorts before timed ones.
+pub fn next_checkin(
+    conn: &Connection,
+    now: DateTime<Local>,
+) -> Result<Option<NextCheckin>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT c.id, p.id, p.name, c.next_checkin_date, c.reminder_time
+       FROM checkins c
+       INNER JOIN checkin_people p ON p.id = c.person_id
+       WHERE c.next_checkin_date IS NOT NULL AND c.next_checkin_date >= ?1",
+        )
+        .map_err(|err| err.to_string())?;
+    let today_value = now.date_naive().format("%Y-%m-%d").to_string();
+    let rows: Vec<NextCheckin> = stmt
+        .query_map(params![today_value], |row| {
+            Ok(NextCheckin {
+                checkin_id: row.get(0)?,
+                person_id: row.get(1)?,
+                person_name: row.get(2)?,
+                next_checkin_date: row.get(3)?,
+                reminder_time: row.get(4)?,
+            })
+        })
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+
+    let mut soonest: Option<(chrono::NaiveDateTime, NextCheckin)> = None;
+    for candidate in rows {
+        let date = parse_date(&candidate.next_checkin_date)?;
+        let time = match candidate.reminder_time.as_deref() {
+            Some(value) => Some(parse_time(value)?),
+            None => None,
+        };
+        if let Some(time) = time {
+            if date.and_time(time) < now.naive_local() {
+                continue;
+            }
+        }
+        let at = date.and_time(time.unwrap_or(NaiveTime::MIN));
+        let is_sooner = match &soonest {
+            Some((best, _)) => at < *best,
+            None => true,
+        };
+        if is_sooner {
+            soonest = Some((at, candidate));
+        }
+    }
+    Ok(soonest.map(|(_, next)| next))
+}
+
+pub fn list_due_task_reminders(
+    conn: &Connection,
+    now: DateTime<Local>,
+) -> Result<Vec<TaskReminder>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, title, reminder_at
+       FROM tasks
+       WHERE reminder_state = 'scheduled'
+         AND reminder_at IS NOT NULL
+         AND status != 'done'
+         AND archived = 0",
+        )
+        .map_err(|err| err.to_string())?;
+
+    let rows: Vec<(String, String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .map_err(|err| err.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+
+    let mut due = Vec::new();
+    for (task_id, title, reminder_at) in rows {
+        let at = DateTime::parse_from_rfc3339(&reminder_at).map_err(|err| err.to_string())?;
+        if at <= now {
+            due.push(TaskReminder {
+                task_id,
+                title,
+                reminder_at,
+            });
+        }
+    }
+    Ok(due)
+}
+
+pub fn mark_task_reminder_sent(conn: &Connection, task_id: &str) -> Result<(), String> {
+    conn.execute(
+        "UPDATE tasks SET reminder_state = 'sent', updated_at = ?1 WHERE id = ?2",
+        params![Utc::now().to_rfc3339(), task_id],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
 pub fn mark_checkin_reminder_sent(conn: &Connection, checkin_id: &str) -> Result<(), String> {
     conn.execute(
         "UPDATE checkins SET reminder_state = 'sent', updated_at = ?1 WHERE id = ?2",
@@ -1379,3 +5121,585 @@ pub fn mark_checkin_reminder_sent(conn: &Connection, checkin_id: &str) -> Result
     .map_err(|err| err.to_string())?;
     Ok(())
 }
+
+/// Records that the user dealt with a check-in's reminder, as opposed to it only having
+/// been shown. Editing the check-in's schedule re-arms it as usual.
+pub fn acknowledge_checkin_reminder(
+    conn: &Connection,
+    checkin_id: &str,
+) -> Result<Checkin, String> {
+    let updated = conn
+        .execute(
+            "UPDATE checkins SET reminder_state = 'acknowledged', updated_at = ?1 WHERE id = ?2",
+            params![Utc::now().to_rfc3339(), checkin_id],
+        )
+        .map_err(|err| err.to_string())?;
+    if updated == 0 {
+        return Err("Check-in not found".to_string());
+    }
+    get_checkin(conn, checkin_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_checkin_before_checkin_is_rejected() {
+        assert!(validate_checkin_schedule("2026-02-06", Some("2026-02-05"), false, None).is_err());
+        assert!(validate_checkin_schedule("2026-02-06", Some("2026-02-06"), false, None).is_ok());
+    }
+
+    #[test]
+    fn enabled_reminder_requires_next_checkin_date() {
+        assert!(validate_checkin_schedule("2026-02-06", None, true, Some("09:00")).is_err());
+        assert!(validate_checkin_schedule("2026-02-06", None, false, Some("09:00")).is_ok());
+        assert!(
+            validate_checkin_schedule("2026-02-06", Some("2026-02-13"), true, Some("09:00"))
+                .is_ok()
+        );
+    }
+
+    fn recurring_input(policy: &str, target_date: &str) -> TaskInput {
+        TaskInput {
+            title: "Stretch".to_string(),
+            notes: None,
+            tags: Vec::new(),
+            target_date: Some(target_date.to_string()),
+            status: "todo".to_string(),
+            progress_percent: 0,
+            deadline_at: None,
+            deadline_all_day: false,
+            is_recurring: true,
+            recurrence_type: Some("daily".to_string()),
+            recurrence_interval: Some(1),
+            recurrence_weekdays: None,
+            catchup_policy: Some(policy.to_string()),
+            recurrence_paused: None,
+            recurrence_reset_notes: false,
+            recurrence_keep_missed: false,
+            timer_enabled: false,
+            timer_minutes: None,
+            timer_seconds: None,
+            reminder_at: None,
+            project_id: None,
+        }
+    }
+
+    fn checkin_input(person_id: &str, checkin_date: &str) -> CheckinInput {
+        CheckinInput {
+            person_id: person_id.to_string(),
+            checkin_date: checkin_date.to_string(),
+            discussion: None,
+            notes: None,
+            action_items: None,
+            next_checkin_date: None,
+            reminder_enabled: false,
+            reminder_time: None,
+        }
+    }
+
+    fn test_conn() -> Connection {
+        let dir = std::env::temp_dir().join(format!("dayrally-test-{}", Uuid::new_v4()));
+        crate::db::open_db(&dir).unwrap()
+    }
+
+    fn task_dates(conn: &Connection) -> Vec<String> {
+        let mut stmt = conn
+            .prepare("SELECT target_date FROM tasks ORDER BY target_date ASC")
+            .unwrap();
+        stmt.query_map([], |row| row.get(0))
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+    }
+
+    #[test]
+    fn skip_policy_collapses_missed_days() {
+        let conn = test_conn();
+        create_task(&conn, recurring_input("skip", "2026-02-01")).unwrap();
+        ensure_recurrences(&conn, "2026-02-06").unwrap();
+        assert_eq!(task_dates(&conn), vec!["2026-02-06"]);
+    }
+
+    #[test]
+    fn all_policy_creates_every_missed_day() {
+        let conn = test_conn();
+        let task = create_task(&conn, recurring_input("all", "2026-02-01")).unwrap();
+        ensure_recurrences(&conn, "2026-02-06").unwrap();
+        assert_eq!(
+            task_dates(&conn),
+            vec![
+                "2026-02-01",
+                "2026-02-02",
+                "2026-02-03",
+                "2026-02-04",
+                "2026-02-05",
+                "2026-02-06"
+            ]
+        );
+        assert_eq!(get_task(&conn, &task.id).unwrap().target_date, "2026-02-06");
+
+        ensure_recurrences(&conn, "2026-02-06").unwrap();
+        assert_eq!(task_dates(&conn).len(), 6);
+    }
+
+    #[test]
+    fn all_policy_rows_are_not_rolled_over() {
+        let conn = test_conn();
+        let task = create_task(&conn, recurring_input("all", "2026-02-01")).unwrap();
+        // Same order as the overview command: roll over first, then recurrences.
+        assert_eq!(
+            rollover_tasks(&conn, "2026-02-06", "all", false).unwrap(),
+            0
+        );
+        ensure_recurrences(&conn, "2026-02-06").unwrap();
+        assert_eq!(task_dates(&conn).len(), 6);
+        let current = get_task(&conn, &task.id).unwrap();
+        assert_eq!(current.target_date, "2026-02-06");
+        assert!(!current.rolled_over);
+    }
+
+    #[test]
+    fn validate_recurrence_reports_what_saving_would_adjust() {
+        assert!(validate_recurrence(&recurring_input("skip", "2026-02-01")).is_empty());
+
+        let mut input = recurring_input("later", "2026-01-31");
+        input.recurrence_type = Some("weekly".to_string());
+        input.recurrence_interval = Some(0);
+        input.recurrence_weekdays = Some("Funday".to_string());
+        let issues: Vec<(String, String)> = validate_recurrence(&input)
+            .into_iter()
+            .map(|issue| (issue.level, issue.field))
+            .collect();
+        let expected = [
+            ("warning", "recurrence_interval"),
+            ("warning", "recurrence_weekdays"),
+            ("warning", "recurrence_weekdays"),
+            ("error", "catchup_policy"),
+        ];
+        assert_eq!(
+            issues,
+            expected
+                .iter()
+                .map(|(level, field)| (level.to_string(), field.to_string()))
+                .collect::<Vec<_>>()
+        );
+
+        input.recurrence_type = Some("monthly".to_string());
+        input.catchup_policy = None;
+        let fields: Vec<String> = validate_recurrence(&input)
+            .into_iter()
+            .map(|issue| issue.field)
+            .collect();
+        assert_eq!(
+            fields,
+            vec!["recurrence_interval", "recurrence_weekdays", "target_date"]
+        );
+    }
+
+    #[test]
+    fn timer_seconds_win_over_minutes_and_fall_back_to_them() {
+        let conn = test_conn();
+        let mut input = plain_task_input("Tea", "2026-02-01");
+        input.timer_enabled = true;
+        input.timer_seconds = Some(90);
+        let task = create_task(&conn, input.clone()).unwrap();
+        assert_eq!(task.timer_seconds, Some(90));
+        assert_eq!(task.timer_minutes, Some(2));
+
+        input.timer_seconds = None;
+        input.timer_minutes = Some(25);
+        let task = update_task(&conn, &task.id, input, None, "2026-02-06").unwrap();
+        assert_eq!(task.timer_seconds, Some(1500));
+    }
+
+    #[test]
+    fn week_preview_projects_each_series_once() {
+        let conn = test_conn();
+        create_task(&conn, recurring_input("skip", "2026-02-01")).unwrap();
+        ensure_recurrences(&conn, "2026-02-03").unwrap();
+        let mut weekly = recurring_input("skip", "2026-02-02");
+        weekly.title = "Review".to_string();
+        weekly.recurrence_type = Some("weekly".to_string());
+        weekly.recurrence_weekdays = Some("Mon,Thu".to_string());
+        create_task(&conn, weekly).unwrap();
+        let before = task_dates(&conn);
+
+        let preview = recurring_week_preview(&conn, "2026-02-02").unwrap();
+        assert_eq!(preview.len(), 7);
+        assert_eq!(preview["2026-02-02"], vec!["Review"]);
+        assert_eq!(preview["2026-02-03"], vec!["Stretch"]);
+        assert_eq!(preview["2026-02-05"], vec!["Review", "Stretch"]);
+        assert_eq!(preview["2026-02-08"], vec!["Stretch"]);
+        assert_eq!(task_dates(&conn), before);
+    }
+
+    #[test]
+    fn week_preview_keeps_days_completed_earlier_in_the_week() {
+        let conn = test_conn();
+        let mut current = create_task(&conn, recurring_input("skip", "2026-02-02")).unwrap();
+        for _ in 0..3 {
+            mark_done_and_generate_next(&conn, &current.id).unwrap();
+            let next_id: String = conn
+                .query_row("SELECT id FROM tasks WHERE status = 'todo'", [], |row| {
+                    row.get(0)
+                })
+                .unwrap();
+            current = get_task(&conn, &next_id).unwrap();
+        }
+        assert_eq!(current.target_date, "2026-02-05");
+
+        let preview = recurring_week_preview(&conn, "2026-02-02").unwrap();
+        for (date, titles) in &preview {
+            assert_eq!(titles, &vec!["Stretch".to_string()], "{date}");
+        }
+    }
+
+    #[test]
+    fn note_folder_names_are_unique_ignoring_case() {
+        let conn = test_conn();
+        create_note_folder(&conn, "Work").unwrap();
+        let err = create_note_folder(&conn, " work ").unwrap_err();
+        assert!(err.contains("already exists"));
+        create_note_folder(&conn, "Workshop").unwrap();
+    }
+
+    #[test]
+    fn keep_missed_leaves_missed_days_in_place() {
+        let conn = test_conn();
+        let mut input = recurring_input("skip", "2026-02-01");
+        input.recurrence_keep_missed = true;
+        let task = create_task(&conn, input).unwrap();
+        ensure_recurrences(&conn, "2026-02-06").unwrap();
+        ensure_recurrences(&conn, "2026-02-06").unwrap();
+        assert_eq!(task_dates(&conn), vec!["2026-02-01", "2026-02-06"]);
+        assert_eq!(get_task(&conn, &task.id).unwrap().target_date, "2026-02-01");
+
+        ensure_recurrences(&conn, "2026-02-08").unwrap();
+        assert_eq!(
+            task_dates(&conn),
+            vec!["2026-02-01", "2026-02-06", "2026-02-08"]
+        );
+
+        let overview = list_overview(&conn, "2026-02-08", Utc::now()).unwrap();
+        assert!(overview.overdue.is_empty());
+        assert_eq!(overview.today.len(), 1);
+    }
+
+    #[test]
+    fn resetting_a_series_unlinks_notes_of_deleted_occurrences() {
+        let conn = test_conn();
+        let task = create_task(&conn, recurring_input("skip", "2026-02-01")).unwrap();
+        let future_id = insert_next_occurrence(&conn, &task, "2026-02-10").unwrap();
+        let note = create_note(
+            &conn,
+            NoteInput {
+                title: "Stretch log".to_string(),
+                body_markdown: String::new(),
+                tags: Vec::new(),
+                folder_id: None,
+                task_id: Some(future_id.clone()),
+                color: None,
+                icon: None,
+            },
+        )
+        .unwrap();
+
+        let reset = reset_recurrence_series(&conn, &task.id, "2026-02-05").unwrap();
+        assert_eq!(reset.deleted, 1);
+        assert!(get_task(&conn, &future_id).is_err());
+        assert_eq!(get_note(&conn, &note.id).unwrap().task_id, None);
+    }
+
+    #[test]
+    fn completion_days_follow_the_app_timezone() {
+        let conn = test_conn();
+        let task = create_task(&conn, plain_task_input("Ship", "2026-02-05")).unwrap();
+        conn.execute(
+            "UPDATE tasks SET status = 'done', done_at = '2026-02-05T20:00:00+00:00' WHERE id = ?1",
+            params![task.id],
+        )
+        .unwrap();
+        let tokyo = Some(chrono_tz::Asia::Tokyo);
+        let utc = Some(chrono_tz::UTC);
+        assert_eq!(
+            completed_between(&conn, "2026-02-06", "2026-02-06", tokyo)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(completed_between(&conn, "2026-02-06", "2026-02-06", utc)
+            .unwrap()
+            .is_empty());
+        let hours = completion_by_hour(&conn, Some("2026-02-06"), "2026-02-06", tokyo).unwrap();
+        assert_eq!(hours[5], 1);
+    }
+
+    #[test]
+    fn promoted_action_items_link_back_to_their_checkin() {
+        let conn = test_conn();
+        let person = create_checkin_person(
+            &conn,
+            CheckinPersonInput {
+                name: "Priya".to_string(),
+                relationship: "peer".to_string(),
+                cadence_interval: None,
+                cadence_unit: None,
+            },
+        )
+        .unwrap();
+        let checkin = create_checkin(&conn, checkin_input(&person.id, "2026-02-02")).unwrap();
+        let task =
+            promote_action_item_to_task(&conn, &checkin.id, "- [ ] Send the deck", "2026-02-03")
+                .unwrap();
+        assert_eq!(task.title, "Send the deck");
+        assert_eq!(task.tags, vec!["Priya"]);
+        assert_eq!(task.source_checkin_id.as_deref(), Some(checkin.id.as_str()));
+        assert!(promote_action_item_to_task(&conn, &checkin.id, "- [ ]", "2026-02-03").is_err());
+
+        delete_checkin(&conn, &checkin.id).unwrap();
+        assert_eq!(get_task(&conn, &task.id).unwrap().source_checkin_id, None);
+    }
+
+    #[test]
+    fn updating_a_checkin_can_clear_its_next_date() {
+        let conn = test_conn();
+        let person = create_checkin_person(
+            &conn,
+            CheckinPersonInput {
+                name: "Sam".to_string(),
+                relationship: "report".to_string(),
+                cadence_interval: Some(1),
+                cadence_unit: Some("week".to_string()),
+            },
+        )
+        .unwrap();
+        let checkin = create_checkin(&conn, checkin_input(&person.id, "2026-02-02")).unwrap();
+        assert_eq!(checkin.next_checkin_date.as_deref(), Some("2026-02-09"));
+
+        let updated = update_checkin(
+            &conn,
+            &checkin.id,
+            checkin_input(&person.id, "2026-02-02"),
+            None,
+        )
+        .unwrap();
+        assert_eq!(updated.next_checkin_date, None);
+    }
+
+    #[test]
+    fn notes_cannot_link_to_missing_tasks() {
+        let conn = test_conn();
+        let input = NoteInput {
+            title: "Orphan".to_string(),
+            body_markdown: String::new(),
+            tags: Vec::new(),
+            folder_id: None,
+            task_id: Some("missing".to_string()),
+            color: None,
+            icon: None,
+        };
+        assert_eq!(
+            create_note(&conn, input.clone()).unwrap_err(),
+            "Task not found"
+        );
+        let note = create_note(
+            &conn,
+            NoteInput {
+                task_id: Some(" ".to_string()),
+                ..input.clone()
+            },
+        )
+        .unwrap();
+        assert_eq!(note.task_id, None);
+        assert!(update_note(&conn, &note.id, input, None).is_err());
+    }
+
+    #[test]
+    fn editing_a_paused_task_keeps_it_paused() {
+        let conn = test_conn();
+        let task = create_task(&conn, recurring_input("skip", "2026-02-01")).unwrap();
+        set_recurrence_paused(&conn, &task.id, true, "2026-02-01").unwrap();
+        let mut edit = recurring_input("skip", "2026-02-01");
+        edit.title = "Stretch more".to_string();
+        let edited = update_task(&conn, &task.id, edit.clone(), None, "2026-02-05").unwrap();
+        assert!(edited.recurrence_paused);
+        assert_eq!(edited.target_date, "2026-02-01");
+
+        edit.recurrence_paused = Some(false);
+        let resumed = update_task(&conn, &task.id, edit, None, "2026-02-05").unwrap();
+        assert!(!resumed.recurrence_paused);
+        assert_eq!(resumed.target_date, "2026-02-05");
+    }
+
+    #[test]
+    fn repeated_rollovers_keep_the_original_date() {
+        let conn = test_conn();
+        let task = create_task(&conn, plain_task_input("Renew passport", "2026-02-01")).unwrap();
+        rollover_tasks(&conn, "2026-02-02", "all", false).unwrap();
+        rollover_tasks(&conn, "2026-02-05", "all", false).unwrap();
+        let rolled = get_task(&conn, &task.id).unwrap();
+        assert_eq!(rolled.target_date, "2026-02-05");
+        assert_eq!(rolled.rolled_from_date.as_deref(), Some("2026-02-01"));
+        assert_eq!(rolled.rollover_count, 2);
+    }
+
+    #[test]
+    fn keep_missed_rows_are_not_rolled_over() {
+        let conn = test_conn();
+        let mut input = recurring_input("skip", "2026-02-01");
+        input.recurrence_keep_missed = true;
+        let task = create_task(&conn, input).unwrap();
+        // Same order as the overview command: roll over first, then recurrences.
+        assert_eq!(
+            rollover_tasks(&conn, "2026-02-06", "all", false).unwrap(),
+            0
+        );
+        ensure_recurrences(&conn, "2026-02-06").unwrap();
+        assert_eq!(task_dates(&conn), vec!["2026-02-01", "2026-02-06"]);
+        let missed = get_task(&conn, &task.id).unwrap();
+        assert_eq!(missed.target_date, "2026-02-01");
+        assert!(!missed.rolled_over);
+    }
+
+    #[test]
+    fn next_occurrence_moves_a_timed_deadline_to_its_own_day() {
+        let conn = test_conn();
+        let five_pm = |day: u32| {
+            Local
+                .with_ymd_and_hms(2026, 2, day, 17, 0, 0)
+                .unwrap()
+                .to_rfc3339()
+        };
+        let mut input = recurring_input("skip", "2026-02-06");
+        input.deadline_at = Some(five_pm(6));
+        let task = create_task(&conn, input).unwrap();
+        mark_done_and_generate_next(&conn, &task.id).unwrap();
+
+        let next_id: String = conn
+            .query_row(
+                "SELECT id FROM tasks WHERE target_date = '2026-02-07'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let next = get_task(&conn, &next_id).unwrap();
+        assert_eq!(
+            DateTime::parse_from_rfc3339(next.deadline_at.as_deref().unwrap()).unwrap(),
+            DateTime::parse_from_rfc3339(&five_pm(7)).unwrap()
+        );
+        assert!(!next.deadline_all_day);
+    }
+
+    #[test]
+    fn tag_suggestions_rank_prefix_then_substring() {
+        let tags = ["Backlog", "work", "homework", "Writing", "admin"]
+            .iter()
+            .map(|tag| tag.to_string())
+            .collect();
+        assert_eq!(
+            rank_tags(tags, "W", 4),
+            vec!["work", "Writing", "homework", "admin"]
+        );
+    }
+
+    #[test]
+    fn note_style_validates_color_and_icon() {
+        assert_eq!(
+            normalize_note_style(Some("#A1B2C3"), Some(" 📌 ")).unwrap(),
+            (Some("#a1b2c3".to_string()), Some("📌".to_string()))
+        );
+        assert_eq!(normalize_note_style(Some(""), None).unwrap(), (None, None));
+        assert!(normalize_note_style(Some("red"), None).is_err());
+        assert!(normalize_note_style(None, Some("bookmark-icon")).is_err());
+    }
+
+    #[test]
+    fn deleting_a_project_keeps_its_tasks() {
+        let conn = test_conn();
+        let project = create_project(
+            &conn,
+            ProjectInput {
+                name: "Garden".to_string(),
+                color: Some("#2E7D32".to_string()),
+            },
+        )
+        .unwrap();
+        assert_eq!(project.color.as_deref(), Some("#2e7d32"));
+        let duplicate = ProjectInput {
+            name: " garden ".to_string(),
+            color: None,
+        };
+        assert!(create_project(&conn, duplicate).is_err());
+
+        let task = create_task(&conn, plain_task_input("Plant bulbs", "2024-03-01")).unwrap();
+        let moved = move_task_to_project(&conn, &task.id, Some(&project.id)).unwrap();
+        assert_eq!(moved.project_id.as_deref(), Some(project.id.as_str()));
+        assert_eq!(list_tasks_by_project(&conn, &project.id).unwrap().len(), 1);
+        assert!(move_task_to_project(&conn, &task.id, Some("missing")).is_err());
+
+        let mut edit = plain_task_input("Plant tulip bulbs", "2024-03-01");
+        let edited = update_task(&conn, &task.id, edit.clone(), None, "2024-03-01").unwrap();
+        assert_eq!(edited.project_id.as_deref(), Some(project.id.as_str()));
+        edit.project_id = Some(String::new());
+        assert_eq!(
+            update_task(&conn, &task.id, edit, None, "2024-03-01")
+                .unwrap()
+                .project_id,
+            None
+        );
+        move_task_to_project(&conn, &task.id, Some(&project.id)).unwrap();
+
+        delete_project(&conn, &project.id).unwrap();
+        assert_eq!(get_task(&conn, &task.id).unwrap().project_id, None);
+        assert!(list_projects(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn patching_status_to_done_matches_update_status() {
+        let conn = test_conn();
+        let mut input = plain_task_input("File taxes", "2024-03-01");
+        input.reminder_at = Some("2024-03-01T09:00:00Z".to_string());
+        let task = create_task(&conn, input).unwrap();
+        let patch = TaskPatch {
+            title: Some("File taxes early".to_string()),
+            status: Some("done".to_string()),
+            ..TaskPatch::default()
+        };
+        let done = patch_task(&conn, &task.id, patch).unwrap();
+        assert_eq!(done.title, "File taxes early");
+        assert_eq!(done.status, "done");
+        assert!(done.done_at.is_some());
+        assert_eq!(done.reminder_at, None);
+        assert_eq!(done.reminder_state, None);
+    }
+
+    #[test]
+    fn snoozed_tasks_leave_today_until_the_snooze_ends() {
+        let conn = test_conn();
+        let task = create_task(&conn, plain_task_input("Call bank", "2024-03-01")).unwrap();
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        assert!(snooze_task(&conn, &task.id, "2024-03-01T08:00:00Z", now).is_err());
+        snooze_task(&conn, &task.id, "2024-03-01T15:00:00+01:00", now).unwrap();
+
+        assert!(list_overview(&conn, "2024-03-01", now)
+            .unwrap()
+            .today
+            .is_empty());
+        let later = Utc.with_ymd_and_hms(2024, 3, 1, 14, 0, 0).unwrap();
+        assert_eq!(
+            list_overview(&conn, "2024-03-01", later)
+                .unwrap()
+                .today
+                .len(),
+            1
+        );
+        assert_eq!(clear_expired_snoozes(&conn, now).unwrap(), 0);
+        assert_eq!(clear_expired_snoozes(&conn, later).unwrap(), 1);
+
+        snooze_task(&conn, &task.id, "2024-03-01T15:00:00Z", now).unwrap();
+        let started = update_status(&conn, &task.id, "in_progress").unwrap();
+        assert_eq!(started.snooze_until, None);
+    }
+}
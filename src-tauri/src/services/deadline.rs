@@ -0,0 +1,138 @@
+use chrono::{
+    DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, TimeZone,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Deadline {
+    Date(NaiveDate),
+    Timestamp(DateTime<Local>),
+}
+
+pub fn parse_deadline(value: &str) -> Result<Deadline, String> {
+    let value = value.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(Deadline::Date(date));
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|parsed| Deadline::Timestamp(parsed.with_timezone(&Local)))
+        .map_err(|_| format!("Invalid deadline: {}", value))
+}
+
+/// Stored form of a deadline: RFC3339 in local time, to the second. A plain date becomes
+/// local midnight, so callers must keep it all-day for the cutoff to stay at 23:59 of
+/// that date.
+pub fn canonical_deadline(value: &str) -> Result<String, String> {
+    let at = match parse_deadline(value)? {
+        Deadline::Date(date) => Local
+            .from_local_datetime(&date.and_time(NaiveTime::MIN))
+            .earliest()
+            .ok_or_else(|| format!("Invalid deadline: {}", value.trim()))?,
+        Deadline::Timestamp(at) => at,
+    };
+    Ok(at.to_rfc3339_opts(SecondsFormat::Secs, false))
+}
+
+/// Moves a deadline by whole days, keeping its local wall-clock time (so a 17:00 deadline
+/// stays at 17:00 across a DST change). Returns the canonical stored form.
+pub fn shift_deadline(value: &str, days: i64) -> Result<String, String> {
+    let local = match parse_deadline(value)? {
+        Deadline::Date(date) => date.and_time(NaiveTime::MIN),
+        Deadline::Timestamp(at) => at.naive_local(),
+    } + Duration::days(days);
+    let at = Local
+        .from_local_datetime(&local)
+        .earliest()
+        .ok_or_else(|| format!("Invalid deadline: {}", value.trim()))?;
+    Ok(at.to_rfc3339_opts(SecondsFormat::Secs, false))
+}
+
+/// Moment the deadline passes. All-day deadlines (and plain dates) run until 23:59 local.
+/// An all-day timestamp keeps the date it was written with, in its stored offset, so a
+/// later timezone change does not move it to another day.
+pub fn deadline_cutoff(value: &str, all_day: bool) -> Result<NaiveDateTime, String> {
+    let end_of_day = NaiveTime::from_hms_opt(23, 59, 0).unwrap();
+    match parse_deadline(value)? {
+        Deadline::Date(date) => Ok(date.and_time(end_of_day)),
+        Deadline::Timestamp(_) if all_day => DateTime::parse_from_rfc3339(value.trim())
+            .map(|stored| stored.date_naive().and_time(end_of_day))
+            .map_err(|_| format!("Invalid deadline: {}", value.trim())),
+        Deadline::Timestamp(at) => Ok(at.naive_local()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local_at(date: NaiveDate, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .from_local_datetime(&date.and_hms_opt(hour, minute, 0).unwrap())
+            .single()
+            .unwrap()
+    }
+
+    #[test]
+    fn shifting_keeps_the_local_time() {
+        let date = NaiveDate::from_ymd_opt(2026, 3, 20).unwrap();
+        let value = local_at(date, 17, 0).to_rfc3339();
+        let shifted = shift_deadline(&value, 14).unwrap();
+        assert_eq!(
+            DateTime::parse_from_rfc3339(&shifted)
+                .unwrap()
+                .with_timezone(&Local),
+            local_at(NaiveDate::from_ymd_opt(2026, 4, 3).unwrap(), 17, 0)
+        );
+    }
+
+    #[test]
+    fn plain_date_runs_until_end_of_day() {
+        let date = NaiveDate::from_ymd_opt(2026, 2, 6).unwrap();
+        let cutoff = deadline_cutoff("2026-02-06", false).unwrap();
+        assert_eq!(cutoff, date.and_hms_opt(23, 59, 0).unwrap());
+        assert!(cutoff > local_at(date, 0, 1).naive_local());
+    }
+
+    #[test]
+    fn all_day_timestamp_uses_its_local_date() {
+        let date = NaiveDate::from_ymd_opt(2026, 2, 6).unwrap();
+        let stamp = local_at(date, 9, 30).to_rfc3339();
+        let evening = local_at(date, 18, 0).naive_local();
+        assert!(deadline_cutoff(&stamp, true).unwrap() > evening);
+        assert!(deadline_cutoff(&stamp, false).unwrap() < evening);
+    }
+
+    #[test]
+    fn all_day_date_survives_a_timezone_change() {
+        let date = NaiveDate::from_ymd_opt(2026, 2, 6).unwrap();
+        let end_of_day = date.and_hms_opt(23, 59, 0).unwrap();
+        for stored in ["2026-02-06T00:00:00+09:00", "2026-02-06T00:00:00-08:00"] {
+            assert_eq!(deadline_cutoff(stored, true).unwrap(), end_of_day);
+        }
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(parse_deadline("next tuesday").is_err());
+        assert!(canonical_deadline("2026-02-30").is_err());
+    }
+
+    #[test]
+    fn canonical_date_only_is_local_midnight() {
+        let date = NaiveDate::from_ymd_opt(2026, 2, 6).unwrap();
+        let canonical = canonical_deadline(" 2026-02-06 ").unwrap();
+        assert_eq!(canonical, local_at(date, 0, 0).to_rfc3339());
+        assert_eq!(
+            deadline_cutoff(&canonical, true).unwrap(),
+            deadline_cutoff("2026-02-06", false).unwrap()
+        );
+    }
+
+    #[test]
+    fn canonical_timestamp_keeps_the_instant() {
+        let canonical = canonical_deadline("2026-02-06T09:30:15.250Z").unwrap();
+        let parsed = DateTime::parse_from_rfc3339(&canonical).unwrap();
+        let original = DateTime::parse_from_rfc3339("2026-02-06T09:30:15Z").unwrap();
+        assert_eq!(parsed, original);
+        assert_eq!(canonical, canonical_deadline(&canonical).unwrap());
+    }
+}
@@ -0,0 +1,309 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecklistItem {
+    pub title: String,
+    pub done: bool,
+    /// Lines indented under the item, trimmed, one per line.
+    pub notes: Option<String>,
+}
+
+/// `- [ ]` / `- [x]` items in order. Lines indented deeper than an item belong to its notes;
+/// anything else that is not a checklist line is skipped.
+pub fn parse_checklist(markdown: &str) -> Vec<ChecklistItem> {
+    let mut items: Vec<ChecklistItem> = Vec::new();
+    let mut open_indent: Option<usize> = None;
+    for line in markdown.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        if open_indent.is_some_and(|item_indent| indent > item_indent) {
+            let item = items.last_mut().expect("open item");
+            let notes = item.notes.get_or_insert_with(String::new);
+            if !notes.is_empty() {
+                notes.push('\n');
+            }
+            notes.push_str(line.trim());
+            continue;
+        }
+        match checklist_line(line.trim()) {
+            Some((done, title)) => {
+                items.push(ChecklistItem {
+                    title: title.to_string(),
+                    done,
+                    notes: None,
+                });
+                open_indent = Some(indent);
+            }
+            None => open_indent = None,
+        }
+    }
+    items
+}
+
+/// Text of a single action-item line without its checkbox or bullet marker.
+pub fn list_item_text(line: &str) -> &str {
+    let line = line.trim();
+    let rest = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|marker| line.strip_prefix(marker))
+        .unwrap_or(line)
+        .trim_start();
+    ["[ ]", "[x]", "[X]"]
+        .iter()
+        .find_map(|checkbox| rest.strip_prefix(checkbox))
+        .unwrap_or(rest)
+        .trim()
+}
+
+fn checklist_line(line: &str) -> Option<(bool, &str)> {
+    let rest = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|marker| line.strip_prefix(marker))?;
+    let (done, title) = if let Some(title) = rest.strip_prefix("[ ]") {
+        (false, title)
+    } else if let Some(title) = rest
+        .strip_prefix("[x]")
+        .or_else(|| rest.strip_prefix("[X]"))
+    {
+        (true, title)
+    } else {
+        return None;
+    };
+    let title = title.trim();
+    if title.is_empty() {
+        None
+    } else {
+        Some((done, title))
+    }
+}
+
+pub fn strip_markdown(value: &str) -> String {
+    let mut out = Vec::new();
+    let mut in_fence = false;
+    for line in value.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            out.push(trimmed.to_string());
+            continue;
+        }
+        if is_rule(trimmed) {
+            continue;
+        }
+        out.push(strip_inline(strip_block_prefix(trimmed)));
+    }
+    out.join("\n").trim().to_string()
+}
+
+/// One-line plaintext snippet of `value` of at most `max_chars` characters, ending in
+/// an ellipsis when cut.
+pub fn preview_text(value: &str, max_chars: usize) -> String {
+    let plain = strip_markdown(value)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if plain.chars().count() <= max_chars {
+        return plain;
+    }
+    let cut: String = plain.chars().take(max_chars.saturating_sub(1)).collect();
+    format!("{}…", cut.trim_end())
+}
+
+/// Distinct `[[Title]]` targets in order of first appearance, compared case-insensitively.
+pub fn extract_wikilinks(value: &str) -> Vec<String> {
+    let mut links: Vec<String> = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("[[") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("]]") else {
+            break;
+        };
+        let target = after[..end].trim();
+        if !target.is_empty()
+            && !target.contains('\n')
+            && !links
+                .iter()
+                .any(|link| link.to_lowercase() == target.to_lowercase())
+        {
+            links.push(target.to_string());
+        }
+        rest = &after[end + 2..];
+    }
+    links
+}
+
+fn is_rule(line: &str) -> bool {
+    let compact: String = line.chars().filter(|ch| !ch.is_whitespace()).collect();
+    compact.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|marker| compact.chars().all(|ch| ch == *marker))
+}
+
+fn strip_block_prefix(line: &str) -> &str {
+    let mut rest = line;
+    loop {
+        let next = rest.trim_start();
+        let next = if let Some(stripped) = next.strip_prefix('>') {
+            stripped
+        } else if next.starts_with('#') {
+            next.trim_start_matches('#')
+        } else if let Some(stripped) = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|marker| next.strip_prefix(marker))
+        {
+            stripped
+        } else if let Some(stripped) = strip_ordered_marker(next) {
+            stripped
+        } else if let Some(stripped) = ["[ ] ", "[x] ", "[X] "]
+            .iter()
+            .find_map(|marker| next.strip_prefix(marker))
+        {
+            stripped
+        } else {
+            return next;
+        };
+        rest = next;
+    }
+}
+
+fn strip_ordered_marker(line: &str) -> Option<&str> {
+    let digits = line.chars().take_while(|ch| ch.is_ascii_digit()).count();
+    if digits == 0 {
+        return None;
+    }
+    line[digits..]
+        .strip_prefix(". ")
+        .or_else(|| line[digits..].strip_prefix(") "))
+}
+
+fn strip_inline(line: &str) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut out = String::with_capacity(line.len());
+    let mut index = 0;
+    while index < chars.len() {
+        let ch = chars[index];
+        match ch {
+            '!' if chars.get(index + 1) == Some(&'[') => {
+                index += 1;
+            }
+            '[' if chars.get(index + 1) == Some(&'[') => {
+                if let Some(end) = wikilink_end(&chars, index) {
+                    out.extend(&chars[index + 2..end]);
+                    index = end + 2;
+                    continue;
+                }
+                out.push(ch);
+                index += 1;
+            }
+            '[' => {
+                if let Some((label, next)) = link_label(&chars, index) {
+                    out.push_str(&strip_inline(&label));
+                    index = next;
+                    continue;
+                }
+                out.push(ch);
+                index += 1;
+            }
+            '*' | '_' | '~' | '`' => {
+                index += 1;
+            }
+            _ => {
+                out.push(ch);
+                index += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Index of the `]]` closing the `[[` at `start`, if it closes on the same line.
+fn wikilink_end(chars: &[char], start: usize) -> Option<usize> {
+    (start + 2..chars.len().saturating_sub(1))
+        .find(|&index| chars[index] == ']' && chars[index + 1] == ']')
+}
+
+/// Returns the label of `[label](target)` starting at `start` and the index just past it.
+fn link_label(chars: &[char], start: usize) -> Option<(String, usize)> {
+    let close = start + chars[start..].iter().position(|ch| *ch == ']')?;
+    if chars.get(close + 1) != Some(&'(') {
+        return None;
+    }
+    let end = close + 1 + chars[close + 1..].iter().position(|ch| *ch == ')')?;
+    Some((chars[start + 1..close].iter().collect(), end + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_block_and_inline_syntax() {
+        let body =
+            "# Title\n\n- [x] **done** item\n> quoted `code`\n---\n1. see [docs](https://x.y)";
+        assert_eq!(
+            strip_markdown(body),
+            "Title\n\ndone item\nquoted code\nsee docs"
+        );
+    }
+
+    #[test]
+    fn previews_are_single_line_and_truncated() {
+        let body = "# Plan\n\n```\nlet x = 1;\n```\nSee [[Weekly Plan]] and [docs](https://x.y)";
+        assert_eq!(
+            preview_text(body, 200),
+            "Plan let x = 1; See Weekly Plan and docs"
+        );
+        assert_eq!(preview_text(body, 10), "Plan let…");
+        assert_eq!(preview_text("", 10), "");
+    }
+
+    #[test]
+    fn wikilinks_are_trimmed_and_deduplicated() {
+        assert_eq!(
+            extract_wikilinks(
+                "See [[ Weekly Plan ]] and [[weekly plan]], then [[Ideas]]. [[]] [[open"
+            ),
+            vec!["Weekly Plan", "Ideas"]
+        );
+    }
+
+    #[test]
+    fn checklist_items_collect_indented_notes() {
+        let body = "# Todo\n- [ ] Write report\n  - outline\n  - [ ] charts\nloose line\n* [x] Call Sam\n- plain bullet\n  ignored";
+        assert_eq!(
+            parse_checklist(body),
+            vec![
+                ChecklistItem {
+                    title: "Write report".to_string(),
+                    done: false,
+                    notes: Some("- outline\n- [ ] charts".to_string()),
+                },
+                ChecklistItem {
+                    title: "Call Sam".to_string(),
+                    done: true,
+                    notes: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn list_item_text_drops_bullets_and_checkboxes() {
+        assert_eq!(list_item_text("  - [x] foo "), "foo");
+        assert_eq!(list_item_text("* bar"), "bar");
+        assert_eq!(list_item_text("plain text"), "plain text");
+        assert_eq!(list_item_text("- [ ]"), "");
+    }
+
+    #[test]
+    fn images_keep_alt_text() {
+        assert_eq!(
+            strip_markdown("before ![diagram](attachments/n/1.png) after"),
+            "before diagram after"
+        );
+    }
+}
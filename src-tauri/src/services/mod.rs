@@ -1,3 +1,6 @@
+pub mod deadline;
+pub mod markdown;
 pub mod recurrence;
 pub mod rollover;
 pub mod timer;
+pub mod undo;
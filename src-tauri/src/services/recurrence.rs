@@ -98,6 +98,21 @@ pub fn next_occurrence(rule: &RecurrenceRule, from: NaiveDate) -> NaiveDate {
     }
 }
 
+/// Maps a check-in cadence (`interval` + `day`/`week`/`month`) onto a recurrence rule.
+pub fn cadence_rule(interval: i64, unit: &str) -> Result<RecurrenceRule, String> {
+    if interval < 1 {
+        return Err("Cadence interval must be at least 1".to_string());
+    }
+    match unit {
+        "day" => Ok(RecurrenceRule::Daily { interval }),
+        "week" => Ok(RecurrenceRule::Daily {
+            interval: interval * 7,
+        }),
+        "month" => Ok(RecurrenceRule::Monthly { interval }),
+        _ => Err("Cadence unit must be one of: day, week, month".to_string()),
+    }
+}
+
 pub fn today_date() -> NaiveDate {
     let now = Local::now();
     NaiveDate::from_ymd_opt(now.year(), now.month(), now.day()).unwrap()
@@ -128,6 +143,17 @@ mod tests {
         assert!(next.weekday() == Weekday::Mon || next.weekday() == Weekday::Wed);
     }
 
+    #[test]
+    fn biweekly_cadence_advances_fourteen_days() {
+        let rule = cadence_rule(2, "week").unwrap();
+        let start = NaiveDate::from_ymd_opt(2026, 2, 6).unwrap();
+        assert_eq!(
+            next_occurrence(&rule, start),
+            NaiveDate::from_ymd_opt(2026, 2, 20).unwrap()
+        );
+        assert!(cadence_rule(1, "year").is_err());
+    }
+
     #[test]
     fn monthly_clamps_to_end_of_month() {
         let rule = RecurrenceRule::Monthly { interval: 1 };
@@ -1,5 +1,17 @@
 use chrono::{DateTime, Local};
-use std::{collections::HashMap, sync::Mutex};
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+/// Longest single sleep of a timer watcher. Sleeps measure elapsed monotonic time, which
+/// can stall while the machine is suspended, so the wall clock is re-checked this often.
+pub const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchStep {
+    Sleep(Duration),
+    Finish,
+    /// The timer was stopped or extended; another watcher owns it now.
+    Abandon,
+}
 
 #[derive(Debug, Clone)]
 pub struct TimerEntry {
@@ -34,3 +46,72 @@ impl TimerState {
         guard.get(task_id).cloned()
     }
 }
+
+/// Next action for a watcher started for a timer ending at `ends_at`, given the entry
+/// currently registered for the task.
+pub fn watch_step(
+    entry: Option<&TimerEntry>,
+    ends_at: DateTime<Local>,
+    now: DateTime<Local>,
+) -> WatchStep {
+    let still_running = entry
+        .map(|entry| entry.ends_at.timestamp() == ends_at.timestamp())
+        .unwrap_or(false);
+    if !still_running {
+        return WatchStep::Abandon;
+    }
+    match (ends_at - now).to_std() {
+        Ok(remaining) if !remaining.is_zero() => WatchStep::Sleep(remaining.min(WATCH_INTERVAL)),
+        _ => WatchStep::Finish,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(ends_at: DateTime<Local>) -> TimerEntry {
+        TimerEntry {
+            task_id: "t1".to_string(),
+            title: "Focus".to_string(),
+            ends_at,
+        }
+    }
+
+    #[test]
+    fn sleeps_in_short_steps_until_the_end() {
+        let now = Local::now();
+        let ends_at = now + chrono::Duration::minutes(25);
+        assert_eq!(
+            watch_step(Some(&entry(ends_at)), ends_at, now),
+            WatchStep::Sleep(WATCH_INTERVAL)
+        );
+        let almost = ends_at - chrono::Duration::seconds(2);
+        assert_eq!(
+            watch_step(Some(&entry(ends_at)), ends_at, almost),
+            WatchStep::Sleep(Duration::from_secs(2))
+        );
+    }
+
+    #[test]
+    fn finishes_immediately_after_a_long_suspend() {
+        let ends_at = Local::now();
+        let woke_at = ends_at + chrono::Duration::hours(3);
+        assert_eq!(
+            watch_step(Some(&entry(ends_at)), ends_at, woke_at),
+            WatchStep::Finish
+        );
+    }
+
+    #[test]
+    fn abandons_stopped_or_extended_timers() {
+        let now = Local::now();
+        let ends_at = now + chrono::Duration::minutes(5);
+        assert_eq!(watch_step(None, ends_at, now), WatchStep::Abandon);
+        let extended = entry(ends_at + chrono::Duration::minutes(5));
+        assert_eq!(
+            watch_step(Some(&extended), ends_at, now),
+            WatchStep::Abandon
+        );
+    }
+}
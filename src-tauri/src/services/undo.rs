@@ -0,0 +1,33 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::Mutex;
+
+/// A deleted task, note or check-in, serialized as it was just before deletion.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeletedEntity {
+    pub kind: String,
+    pub data: Value,
+}
+
+/// One-level undo: only the most recent delete can be restored.
+#[derive(Default)]
+pub struct UndoState {
+    pub last_delete: Mutex<Option<DeletedEntity>>,
+}
+
+impl UndoState {
+    pub fn push(&self, entity: DeletedEntity) {
+        let mut guard = self.last_delete.lock().expect("undo mutex");
+        *guard = Some(entity);
+    }
+
+    pub fn take(&self) -> Option<DeletedEntity> {
+        let mut guard = self.last_delete.lock().expect("undo mutex");
+        guard.take()
+    }
+
+    pub fn clear(&self) {
+        let mut guard = self.last_delete.lock().expect("undo mutex");
+        *guard = None;
+    }
+}
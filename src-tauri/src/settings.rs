@@ -1,10 +1,48 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 use std::{fs, path::PathBuf};
 use tauri::{AppHandle, Manager};
 
+const ROLLOVER_MODES: &[&str] = &["all", "recent", "off"];
+pub const DEFAULT_MAX_NOTE_ATTACHMENTS: u32 = 500;
+pub const DEFAULT_MAX_NOTE_ATTACHMENT_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Every field must tolerate being absent so older settings.json files keep loading.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct Settings {
     pub workspace_path: Option<String>,
+    /// IANA timezone name (e.g. `Europe/Berlin`) that decides when the day flips.
+    /// Falls back to the system local timezone when unset.
+    pub timezone: Option<String>,
+    /// Focus mode: starting a timer stops whichever other timer is running.
+    pub single_timer: bool,
+    /// Moving a timer-enabled task to "in_progress" starts its timer.
+    #[serde(alias = "auto_timer_on_doing")]
+    pub auto_timer_on_in_progress: bool,
+    /// Append "— rolled over from <date>" to a task's notes each time it rolls over.
+    pub log_rollovers: bool,
+    /// Which unfinished past tasks move to today: "all" (default), "recent" (only
+    /// yesterday's) or "off" (they stay put and show as overdue).
+    pub rollover_mode: Option<String>,
+    /// Serve read-only JSON (`GET /today`, `GET /timers`) on 127.0.0.1 for scripts.
+    pub local_api_enabled: bool,
+    /// Port for the local API; `local_api::DEFAULT_PORT` when unset.
+    pub local_api_port: Option<u16>,
+    /// Notification sound names for timers, check-in reminders and task reminders.
+    /// "none" silences that event; unset uses the system "default" sound.
+    pub timer_sound: Option<String>,
+    pub checkin_reminder_sound: Option<String>,
+    pub deadline_sound: Option<String>,
+    /// Do-not-disturb window (`HH:MM`, app timezone) for background notifications.
+    /// The window may cross midnight, e.g. 22:00–07:00.
+    pub quiet_start: Option<String>,
+    pub quiet_end: Option<String>,
+    /// Per-note attachment caps; `DEFAULT_MAX_NOTE_ATTACHMENTS` and
+    /// `DEFAULT_MAX_NOTE_ATTACHMENT_BYTES` when unset.
+    pub max_note_attachments: Option<u32>,
+    pub max_note_attachment_bytes: Option<u64>,
 }
 
 fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
@@ -29,3 +67,207 @@ pub fn save_settings(app: &AppHandle, settings: &Settings) -> Result<(), String>
     fs::write(&path, data).map_err(|err| err.to_string())?;
     Ok(())
 }
+
+/// Normalizes blank values to `None` and rejects unknown timezones.
+pub fn validate_settings(mut settings: Settings) -> Result<Settings, String> {
+    settings.workspace_path = settings
+        .workspace_path
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    settings.timezone = match settings.timezone.as_deref().map(str::trim) {
+        Some(value) if !value.is_empty() => Some(parse_timezone(value)?.name().to_string()),
+        _ => None,
+    };
+    settings.rollover_mode = match settings.rollover_mode.as_deref().map(str::trim) {
+        Some(value) if !value.is_empty() => {
+            let value = value.to_lowercase();
+            if !ROLLOVER_MODES.contains(&value.as_str()) {
+                return Err(format!("Unknown rollover mode: {}", value));
+            }
+            Some(value)
+        }
+        _ => None,
+    };
+    for sound in [
+        &mut settings.timer_sound,
+        &mut settings.checkin_reminder_sound,
+        &mut settings.deadline_sound,
+    ] {
+        *sound = sound
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| {
+                if value.eq_ignore_ascii_case("none") {
+                    "none".to_string()
+                } else {
+                    value.to_string()
+                }
+            });
+    }
+    settings.quiet_start = normalize_clock_time(settings.quiet_start.as_deref())?;
+    settings.quiet_end = normalize_clock_time(settings.quiet_end.as_deref())?;
+    if settings.quiet_start.is_some() != settings.quiet_end.is_some() {
+        return Err("Quiet hours need both a start and an end time".to_string());
+    }
+    if settings.max_note_attachments == Some(0) || settings.max_note_attachment_bytes == Some(0) {
+        return Err("Attachment limits must be greater than zero".to_string());
+    }
+    if let Some(port) = settings.local_api_port {
+        if port < 1024 {
+            return Err("Local API port must be between 1024 and 65535".to_string());
+        }
+    }
+    Ok(settings)
+}
+
+fn normalize_clock_time(value: Option<&str>) -> Result<Option<String>, String> {
+    match value.map(str::trim) {
+        Some(value) if !value.is_empty() => NaiveTime::parse_from_str(value, "%H:%M")
+            .map(|time| Some(time.format("%H:%M").to_string()))
+            .map_err(|_| format!("Invalid time (expected HH:MM): {}", value)),
+        _ => Ok(None),
+    }
+}
+
+/// Whether `now` falls inside the configured quiet hours. The start is inclusive and the
+/// end exclusive; an equal start and end means no quiet hours.
+pub fn in_quiet_hours(now: DateTime<Utc>, settings: &Settings) -> bool {
+    let parse =
+        |value: Option<&str>| value.and_then(|v| NaiveTime::parse_from_str(v, "%H:%M").ok());
+    let (start, end) = match (
+        parse(settings.quiet_start.as_deref()),
+        parse(settings.quiet_end.as_deref()),
+    ) {
+        (Some(start), Some(end)) => (start, end),
+        _ => return false,
+    };
+    let time = match configured_timezone(settings) {
+        Some(tz) => now.with_timezone(&tz).time(),
+        None => now.with_timezone(&Local).time(),
+    };
+    if start <= end {
+        start <= time && time < end
+    } else {
+        time >= start || time < end
+    }
+}
+
+/// (max attachments, max total bytes) allowed per note.
+pub fn note_attachment_limits(settings: &Settings) -> (usize, u64) {
+    (
+        settings
+            .max_note_attachments
+            .unwrap_or(DEFAULT_MAX_NOTE_ATTACHMENTS) as usize,
+        settings
+            .max_note_attachment_bytes
+            .unwrap_or(DEFAULT_MAX_NOTE_ATTACHMENT_BYTES),
+    )
+}
+
+pub fn rollover_mode(settings: &Settings) -> &str {
+    settings.rollover_mode.as_deref().unwrap_or("all")
+}
+
+/// The sound to pass to a notification, or `None` when the event is silenced.
+pub fn notification_sound(setting: &Option<String>) -> Option<&str> {
+    match setting.as_deref() {
+        Some("none") => None,
+        Some(name) => Some(name),
+        None => Some("default"),
+    }
+}
+
+pub fn parse_timezone(value: &str) -> Result<Tz, String> {
+    value
+        .trim()
+        .parse::<Tz>()
+        .map_err(|_| format!("Unknown timezone: {}", value.trim()))
+}
+
+/// The configured app timezone; `None` means the system local timezone.
+pub fn configured_timezone(settings: &Settings) -> Option<Tz> {
+    settings
+        .timezone
+        .as_deref()
+        .and_then(|name| parse_timezone(name).ok())
+}
+
+pub fn app_today(settings: &Settings) -> NaiveDate {
+    let now = Utc::now();
+    match configured_timezone(settings) {
+        Some(tz) => now.with_timezone(&tz).date_naive(),
+        None => now.with_timezone(&Local).date_naive(),
+    }
+}
+
+/// Current wall-clock time in the app timezone.
+pub fn app_now(settings: &Settings) -> NaiveDateTime {
+    let now = Utc::now();
+    match configured_timezone(settings) {
+        Some(tz) => now.with_timezone(&tz).naive_local(),
+        None => now.with_timezone(&Local).naive_local(),
+    }
+}
+
+/// Seconds until just after the next midnight in the app timezone.
+pub fn seconds_until_next_day(settings: &Settings) -> i64 {
+    let now = Utc::now();
+    match configured_timezone(settings) {
+        Some(tz) => seconds_until_midnight(&tz, now),
+        None => seconds_until_midnight(&Local, now),
+    }
+}
+
+fn seconds_until_midnight<T: TimeZone>(tz: &T, now: DateTime<Utc>) -> i64 {
+    let tomorrow = now.with_timezone(tz).date_naive() + Duration::days(1);
+    let midnight = tomorrow.and_hms_opt(0, 0, 5).unwrap();
+    tz.from_local_datetime(&midnight)
+        .earliest()
+        .map(|at| (at.with_timezone(&Utc) - now).num_seconds())
+        .unwrap_or(60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quiet(start: &str, end: &str) -> Settings {
+        Settings {
+            timezone: Some("UTC".to_string()),
+            quiet_start: Some(start.to_string()),
+            quiet_end: Some(end.to_string()),
+            ..Settings::default()
+        }
+    }
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 2, 6, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn quiet_hours_can_cross_midnight() {
+        let settings = quiet("22:00", "07:00");
+        assert!(in_quiet_hours(at(22, 0), &settings));
+        assert!(in_quiet_hours(at(23, 30), &settings));
+        assert!(in_quiet_hours(at(3, 0), &settings));
+        assert!(!in_quiet_hours(at(7, 0), &settings));
+        assert!(!in_quiet_hours(at(12, 0), &settings));
+    }
+
+    #[test]
+    fn same_day_quiet_hours() {
+        let settings = quiet("12:00", "13:30");
+        assert!(in_quiet_hours(at(12, 45), &settings));
+        assert!(!in_quiet_hours(at(13, 30), &settings));
+        assert!(!in_quiet_hours(at(11, 59), &settings));
+    }
+
+    #[test]
+    fn equal_start_and_end_means_no_quiet_hours() {
+        let settings = quiet("09:00", "09:00");
+        assert!(!in_quiet_hours(at(9, 0), &settings));
+        assert!(!in_quiet_hours(at(21, 0), &settings));
+        assert!(!in_quiet_hours(at(9, 0), &Settings::default()));
+    }
+}